@@ -0,0 +1,564 @@
+use crate::{Game, GameState, Scoreboard, Tile, Variant};
+use std::collections::HashMap;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+// SplitMix64, small and seedable so games and tests are reproducible
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub const fn seeded(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+pub fn choose_move(game: &Game, player: Tile, difficulty: Difficulty, rng: &mut Rng) -> usize {
+    Ai::new().choose_move(game, player, difficulty, rng)
+}
+
+pub fn best_move(game: &Game, player: Tile) -> usize {
+    Ai::new().best_move(game, player)
+}
+
+fn plays_randomly(difficulty: Difficulty, rng: &mut Rng) -> bool {
+    match difficulty {
+        Difficulty::Easy => true,
+        Difficulty::Medium => rng.below(2) == 0,
+        Difficulty::Hard => false,
+    }
+}
+
+pub fn random_move(game: &Game, rng: &mut Rng) -> usize {
+    let empty: Vec<usize> = game.legal_moves().collect();
+    assert!(!empty.is_empty(), "random_move called on a finished game");
+    empty[rng.below(empty.len())]
+}
+
+// A cheap weak opponent that needs no generator: the position itself seeds the
+// pick, so the same board always gets the same reply
+pub fn position_move(game: &Game) -> Option<usize> {
+    let seed = game
+        .board_key()
+        .into_iter()
+        .fold(u64::from(game.turn()), |seed, tile| {
+            seed.wrapping_mul(5).wrapping_add(tile as u64)
+        });
+    game.nth_legal_move(Rng::seeded(seed).below(usize::MAX))
+}
+
+// One full game on a classic board with no I/O, each side at its own difficulty
+pub fn self_play(
+    ai_x: &mut Ai,
+    x_difficulty: Difficulty,
+    ai_o: &mut Ai,
+    o_difficulty: Difficulty,
+) -> GameState {
+    let mut game = Game::new();
+    while !game.game_over() {
+        let player = game.current_player();
+        let index = match player {
+            Tile::X => ai_x.pick_move(&game, player, x_difficulty),
+            Tile::O | Tile::Y | Tile::Empty => ai_o.pick_move(&game, player, o_difficulty),
+        };
+        game.play(index).expect("the AI only picks legal moves");
+    }
+    game.state()
+}
+
+pub fn self_play_many(
+    runs: u32,
+    ai_x: &mut Ai,
+    x_difficulty: Difficulty,
+    ai_o: &mut Ai,
+    o_difficulty: Difficulty,
+) -> Scoreboard {
+    let mut scoreboard = Scoreboard::default();
+    for _ in 0..runs {
+        scoreboard.record(self_play(ai_x, x_difficulty, ai_o, o_difficulty));
+    }
+    scoreboard
+}
+
+// Everything a position's value depends on. The board shape and win length are
+// part of it, since one Ai may be asked about games played under different rules
+#[derive(PartialEq, Eq, Hash)]
+struct Key {
+    cells: Vec<Tile>,
+    player: Tile,
+    variant: Variant,
+    width: usize,
+    height: usize,
+    k: usize,
+}
+
+type Cache = HashMap<Key, (i32, Bound)>;
+
+// Alpha-beta minimax search that remembers the value of every position it has
+// seen, keyed on the canonical board so symmetric positions share an entry
+pub struct Ai {
+    cache: Option<Cache>,
+    pruning: bool,
+    nodes: u64,
+    rng: Rng,
+}
+
+// Whether a cached value is exact or only a bound left by an alpha-beta cutoff
+#[derive(Clone, Copy)]
+enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+impl Ai {
+    pub fn new() -> Self {
+        Self {
+            cache: Some(HashMap::new()),
+            pruning: true,
+            nodes: 0,
+            rng: Rng::seeded(0),
+        }
+    }
+
+    // Same seed and same opponent moves give the same game, even on Easy and Medium
+    pub fn seeded(seed: u64) -> Self {
+        Self {
+            rng: Rng::seeded(seed),
+            ..Self::new()
+        }
+    }
+
+    pub const fn uncached() -> Self {
+        Self {
+            cache: None,
+            pruning: true,
+            nodes: 0,
+            rng: Rng::seeded(0),
+        }
+    }
+
+    // Plain minimax with no cache and no pruning, mostly useful as a baseline
+    pub const fn exhaustive() -> Self {
+        Self {
+            cache: None,
+            pruning: false,
+            nodes: 0,
+            rng: Rng::seeded(0),
+        }
+    }
+
+    // Positions evaluated so far
+    pub const fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    pub fn choose_move(
+        &mut self,
+        game: &Game,
+        player: Tile,
+        difficulty: Difficulty,
+        rng: &mut Rng,
+    ) -> usize {
+        if plays_randomly(difficulty, rng) {
+            random_move(game, rng)
+        } else {
+            self.best_move(game, player)
+        }
+    }
+
+    // choose_move using the Ai's own generator, see Ai::seeded
+    pub fn pick_move(&mut self, game: &Game, player: Tile, difficulty: Difficulty) -> usize {
+        if plays_randomly(difficulty, &mut self.rng) {
+            random_move(game, &mut self.rng)
+        } else {
+            self.best_move(game, player)
+        }
+    }
+
+    pub fn best_move(&mut self, game: &Game, player: Tile) -> usize {
+        let mut best: Option<(usize, i32)> = None;
+        for (index, next) in distinct_children(game) {
+            // Only a strictly better move matters, so the best score so far is a valid bound
+            let score = if player == Tile::X {
+                let alpha = best.map_or(i32::MIN, |(_, score)| score);
+                self.value(&next, alpha, i32::MAX)
+            } else {
+                let beta = best.map_or(i32::MAX, |(_, score)| -score);
+                -self.value(&next, i32::MIN, beta)
+            };
+            if best.is_none_or(|(_, best_score)| score > best_score) {
+                best = Some((index, score));
+            }
+        }
+        best.map(|(index, _)| index)
+            .expect("best_move called on a finished game")
+    }
+
+    // How the game ends from here if both sides play perfectly
+    pub fn forced_result(&mut self, game: &Game) -> GameState {
+        if game.game_over() {
+            return game.state();
+        }
+        match self.value(game, i32::MIN, i32::MAX) {
+            0 => GameState::Draw,
+            value if value > 0 => GameState::Win(Tile::X),
+            _ => GameState::Win(Tile::O),
+        }
+    }
+
+    // Every legal move with its minimax score for `player`, best first
+    pub fn ranked_moves(&mut self, game: &Game, player: Tile) -> Vec<(usize, i32)> {
+        let mut ranked: Vec<(usize, i32)> = game
+            .legal_moves()
+            .map(|index| {
+                let mut next = game.clone();
+                next.play(index).expect("legal moves can always be played");
+                let value = self.value(&next, i32::MIN, i32::MAX);
+                (index, if player == Tile::X { value } else { -value })
+            })
+            .collect();
+        ranked.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        ranked
+    }
+
+    // Value of the position with best play, from X's point of view. Wins count for
+    // more the more squares are left, so a quicker win beats a slower one
+    fn value(&mut self, game: &Game, mut alpha: i32, mut beta: i32) -> i32 {
+        let key = Key {
+            cells: game.canonical(),
+            player: game.player,
+            variant: game.variant,
+            width: game.width,
+            height: game.height,
+            k: game.k,
+        };
+        if let Some(&(value, bound)) = self.cache.as_ref().and_then(|cache| cache.get(&key)) {
+            match bound {
+                Bound::Exact => return value,
+                Bound::Lower => alpha = alpha.max(value),
+                Bound::Upper => beta = beta.min(value),
+            }
+            if alpha >= beta {
+                return value;
+            }
+        }
+        self.nodes += 1;
+        let (original_alpha, original_beta) = (alpha, beta);
+        let value = match game.state() {
+            GameState::Win(Tile::X) => 1 + empty_squares(game),
+            GameState::Win(_) => -1 - empty_squares(game),
+            GameState::Draw => 0,
+            GameState::InProgress => {
+                let maximizing = game.player == Tile::X;
+                let mut best = if maximizing { i32::MIN } else { i32::MAX };
+                for (_, next) in distinct_children(game) {
+                    let value = self.value(&next, alpha, beta);
+                    if maximizing {
+                        best = best.max(value);
+                        alpha = alpha.max(best);
+                    } else {
+                        best = best.min(value);
+                        beta = beta.min(best);
+                    }
+                    if self.pruning && alpha >= beta {
+                        break;
+                    }
+                }
+                best
+            }
+        };
+        if let Some(cache) = self.cache.as_mut() {
+            let bound = if value <= original_alpha {
+                Bound::Upper
+            } else if value >= original_beta {
+                Bound::Lower
+            } else {
+                Bound::Exact
+            };
+            cache.insert(key, (value, bound));
+        }
+        value
+    }
+}
+
+impl Default for Ai {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Game {
+    // A quick static score for `player`: 10 for each of their marks in every line
+    // the opponent hasn't blocked, minus the same for the opponent, or +/-1000 once
+    // the game is won. Under Misere owning open lines is a liability, so it flips
+    pub fn evaluate(&self, player: Tile) -> i32 {
+        assert!(player.is_occupied(), "Invalid player");
+        let opponent = player.opponent();
+        match self.state() {
+            GameState::Win(winner) if winner == player => return 1000,
+            GameState::Win(_) => return -1000,
+            GameState::Draw => return 0,
+            GameState::InProgress => {}
+        }
+        let open_marks = |tile: Tile| {
+            let marks: usize = self
+                .lines()
+                .filter(|line| line.iter().all(|&t| t == tile || t.is_empty()))
+                .map(|line| line.iter().filter(|&&t| t == tile).count())
+                .sum();
+            i32::try_from(marks).expect("a board has fewer than 2^31 lines")
+        };
+        let score = 10 * (open_marks(player) - open_marks(opponent));
+        if self.variant == Variant::Misere {
+            -score
+        } else {
+            score
+        }
+    }
+}
+
+fn empty_squares(game: &Game) -> i32 {
+    let empty = game
+        .board_key()
+        .into_iter()
+        .filter(|&tile| tile.is_empty())
+        .count();
+    i32::try_from(empty).expect("boards have at most 255 squares")
+}
+
+// One child per symmetry class, so mirrored positions are only searched once
+fn distinct_children(game: &Game) -> Vec<(usize, Game)> {
+    let mut seen = Vec::new();
+    let mut children = Vec::new();
+    for index in game.legal_moves() {
+        let mut next = game.clone();
+        next.play(index).expect("legal moves can always be played");
+        let key = next.canonical();
+        if !seen.contains(&key) {
+            seen.push(key);
+            children.push((index, next));
+        }
+    }
+    children
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_never_loses(search: &mut Ai, game: &Game, ai: Tile) {
+        if game.game_over() {
+            assert!(game.winner().is_none_or(|winner| winner == ai));
+            return;
+        }
+        if game.player == ai {
+            let mut next = game.clone();
+            next.play(search.best_move(game, ai)).unwrap();
+            assert_never_loses(search, &next, ai);
+        } else {
+            for index in game.legal_moves() {
+                let mut next = game.clone();
+                next.play(index).unwrap();
+                assert_never_loses(search, &next, ai);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ai_never_loses_as_o() {
+        assert_never_loses(&mut Ai::new(), &Game::new(), Tile::O);
+    }
+
+    #[test]
+    fn test_ai_never_loses_as_x() {
+        assert_never_loses(&mut Ai::new(), &Game::new(), Tile::X);
+    }
+
+    #[test]
+    fn test_misere_avoids_completing_a_line() {
+        let mut game = Game::new();
+        game.set_variant(Variant::Misere);
+        for index in [0, 3, 1, 4] {
+            game.play(index).unwrap();
+        }
+        assert_ne!(Ai::new().best_move(&game, Tile::X), 2);
+        assert_ne!(Ai::exhaustive().best_move(&game, Tile::X), 2);
+    }
+
+    #[test]
+    fn test_ranked_moves_put_the_win_first() {
+        let game = Game::from_moves(&[3, 0, 4, 1, 8]).unwrap();
+        let ranked = game.ranked_moves(Tile::O);
+        assert_eq!(ranked.len(), 4);
+        assert_eq!(ranked[0].0, 2);
+        assert!(ranked.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+        assert!(ranked[0].1 > ranked[1].1);
+    }
+
+    #[test]
+    fn test_evaluate() {
+        assert_eq!(Game::new().evaluate(Tile::X), 0);
+        let game = Game::from_moves(&[0, 4, 1]).unwrap();
+        assert!(game.evaluate(Tile::X) > 0);
+        assert_eq!(game.evaluate(Tile::O), -game.evaluate(Tile::X));
+        let won = Game::from_moves(&[0, 3, 1, 4, 2]).unwrap();
+        assert_eq!(won.evaluate(Tile::X), 1000);
+        assert_eq!(won.evaluate(Tile::O), -1000);
+    }
+
+    #[test]
+    fn test_cache_and_pruning_match_plain_minimax() {
+        let mut cached = Ai::new();
+        let mut uncached = Ai::uncached();
+        let mut exhaustive = Ai::exhaustive();
+        for moves in [
+            &[][..],
+            &[4],
+            &[0, 4],
+            &[0, 4, 8],
+            &[0, 3, 1],
+            &[1, 4, 7, 3],
+        ] {
+            let game = Game::from_moves(moves).unwrap();
+            let player = game.current_player();
+            let expected = exhaustive.best_move(&game, player);
+            assert_eq!(cached.best_move(&game, player), expected);
+            assert_eq!(uncached.best_move(&game, player), expected);
+        }
+        assert!(cached.nodes() * 4 < uncached.nodes());
+        assert!(uncached.nodes() < exhaustive.nodes());
+    }
+
+    #[test]
+    fn test_cache_is_kept_apart_by_rules() {
+        let mut ai = Ai::new();
+        assert_eq!(ai.forced_result(&Game::new()), GameState::Draw);
+        let two_in_a_row = Game::with_rules(3, 3, 2);
+        assert_eq!(ai.forced_result(&two_in_a_row), GameState::Win(Tile::X));
+        assert_eq!(
+            ai.ranked_moves(&two_in_a_row, Tile::X),
+            Ai::new().ranked_moves(&two_in_a_row, Tile::X)
+        );
+        assert_eq!(ai.forced_result(&Game::new()), GameState::Draw);
+    }
+
+    #[test]
+    fn test_pruning_visits_fewer_nodes_on_4x4() {
+        let mut game = Game::with_size(4);
+        for index in [5, 0, 10, 15, 6, 9, 3, 12] {
+            game.play(index).unwrap();
+        }
+        let mut pruned = Ai::uncached();
+        let mut exhaustive = Ai::exhaustive();
+        let player = game.current_player();
+        let pruned_move = pruned.best_move(&game, player);
+        let exhaustive_move = exhaustive.best_move(&game, player);
+        assert_eq!(pruned_move, exhaustive_move);
+        assert!(pruned.nodes() * 2 < exhaustive.nodes());
+    }
+
+    #[test]
+    fn test_easy_sometimes_misses_a_win() {
+        let mut game = Game::new();
+        for index in [0, 3, 1, 4] {
+            game.play(index).unwrap();
+        }
+        let mut rng = Rng::seeded(7);
+        let picks: Vec<usize> = (0..20)
+            .map(|_| choose_move(&game, Tile::X, Difficulty::Easy, &mut rng))
+            .collect();
+        assert!(picks.iter().any(|&index| index != 2));
+        assert!(picks.iter().all(|&index| game.clone().play(index).is_ok()));
+    }
+
+    #[test]
+    fn test_same_seed_replays_the_same_game() {
+        let play = |seed: u64| {
+            let mut ai = Ai::seeded(seed);
+            let mut game = Game::new();
+            // The scripted human always takes the first free square
+            while !game.game_over() {
+                let index = if game.current_player() == Tile::X {
+                    game.legal_moves().next().unwrap()
+                } else {
+                    ai.pick_move(&game, Tile::O, Difficulty::Medium)
+                };
+                game.play(index).unwrap();
+            }
+            game
+        };
+        for seed in 0..5 {
+            assert_eq!(play(seed).grid(), play(seed).grid());
+        }
+        // The seed really does drive Medium's random moves
+        let first = play(0).grid();
+        assert!((1..10).any(|seed| play(seed).grid() != first));
+    }
+
+    #[test]
+    fn test_position_move_is_repeatable() {
+        let mut game = Game::new();
+        while let Some(index) = position_move(&game) {
+            assert_eq!(position_move(&game), Some(index));
+            assert!(game.legal_moves().any(|m| m == index));
+            game.play(index).unwrap();
+        }
+        assert!(game.game_over());
+    }
+
+    #[test]
+    fn test_perfect_self_play_always_draws() {
+        let scoreboard = self_play_many(
+            100,
+            &mut Ai::seeded(1),
+            Difficulty::Hard,
+            &mut Ai::seeded(2),
+            Difficulty::Hard,
+        );
+        assert_eq!(scoreboard.draws, 100);
+    }
+
+    #[test]
+    fn test_self_play_against_random() {
+        let scoreboard = self_play_many(
+            50,
+            &mut Ai::seeded(1),
+            Difficulty::Hard,
+            &mut Ai::seeded(2),
+            Difficulty::Easy,
+        );
+        assert_eq!(scoreboard.o_wins, 0);
+        assert_eq!(scoreboard.x_wins + scoreboard.draws, 50);
+    }
+
+    #[test]
+    fn test_hard_blocks_immediate_win() {
+        let mut game = Game::new();
+        for index in [0, 4, 8, 2] {
+            game.play(index).unwrap();
+        }
+        for seed in 0..10 {
+            let mut rng = Rng::seeded(seed);
+            assert_eq!(choose_move(&game, Tile::X, Difficulty::Hard, &mut rng), 6);
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use quick_start::{ai, Game};
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum OnTimeout {
+    Forfeit,
+    RandomMove,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum TimeoutAction {
+    Forfeit,
+    Play(usize),
+}
+
+// Per-move time limit for human players
+pub struct Clock {
+    pub limit: Duration,
+    pub on_timeout: OnTimeout,
+    pub rng: ai::Rng,
+}
+
+impl Clock {
+    pub fn timeout_action(&mut self, game: &Game) -> TimeoutAction {
+        timeout_action(game, self.on_timeout, &mut self.rng)
+    }
+}
+
+// What happens to the player on move when their time runs out
+pub fn timeout_action(game: &Game, on_timeout: OnTimeout, rng: &mut ai::Rng) -> TimeoutAction {
+    match on_timeout {
+        OnTimeout::RandomMove if !game.game_over() => {
+            TimeoutAction::Play(ai::random_move(game, rng))
+        }
+        OnTimeout::Forfeit | OnTimeout::RandomMove => TimeoutAction::Forfeit,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeout_forfeits() {
+        let game = Game::from_moves(&[4]).unwrap();
+        let mut rng = ai::Rng::seeded(1);
+        assert_eq!(
+            timeout_action(&game, OnTimeout::Forfeit, &mut rng),
+            TimeoutAction::Forfeit
+        );
+    }
+
+    #[test]
+    fn test_timeout_plays_a_legal_move() {
+        let game = Game::from_moves(&[4, 0, 8]).unwrap();
+        let mut rng = ai::Rng::seeded(1);
+        for _ in 0..20 {
+            match timeout_action(&game, OnTimeout::RandomMove, &mut rng) {
+                TimeoutAction::Play(index) => assert!(game.legal_moves().any(|m| m == index)),
+                TimeoutAction::Forfeit => panic!("expected a move"),
+            }
+        }
+    }
+}
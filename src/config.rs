@@ -0,0 +1,464 @@
+use core::fmt::{Display, Formatter};
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use quick_start::{ai::Difficulty, Tile, Variant};
+
+use crate::clock::OnTimeout;
+
+pub const USAGE: &str = "\
+Usage: quick-start [options]
+       quick-start replay <moves>
+       quick-start stats <difficulty> <difficulty> [games]
+       quick-start analyze <notation>
+
+Options:
+  --size=N                  play on an NxN board (3-15, default 3)
+  --no-color                disable colored output
+  --no-bell                 don't ring the terminal bell when someone wins
+  --cb                      color-blind friendly colors for X and O
+  --labels                  show row and column numbers around the board
+  --keypad                  type squares as on a numeric keypad, 7 8 9 along the top
+  --clear                   redraw the board in place instead of scrolling
+  --moves-file=PATH         play the moves in PATH, one per line, before taking input
+  --auto-open=N             play the first N moves perfectly for both sides (3x3 only, not with --wild)
+  --tutorial                point out threats to win and where to block them
+  --tui                     full-screen mode with arrow keys (needs the tui feature)
+  --host=ADDR               wait for a player to connect to ADDR and play X (needs the net feature)
+  --connect=ADDR            join a game hosted at ADDR and play O (needs the net feature)
+  --misere                  completing a line loses instead of wins
+  --wild                    either player may place X or O (not with --ai)
+  --first=x|o               who moves first (default x)
+  --players=2|3             a third player joins as Y (Normal rules, no --ai)
+  --ai[=x|o|none]           let the computer play a side (--ai alone means o, 3x3 only)
+  --difficulty=easy|medium|hard
+  --seed=N                  seed the computer's random moves to replay a game
+  --names=X_NAME,O_NAME
+  --match=N                 play until someone wins N rounds
+  --turn-time=SECS          limit how long each move may take
+  --on-timeout=forfeit|random
+                            what happens when time runs out (default forfeit)";
+
+#[derive(PartialEq, Eq, Debug)]
+pub struct Config {
+    pub size: usize,
+    pub color: bool,
+    pub bell: bool,
+    pub labels: bool,
+    pub color_blind: bool,
+    pub clear: bool,
+    pub keypad: bool,
+    pub tutorial: bool,
+    pub moves_file: Option<PathBuf>,
+    pub auto_open: usize,
+    pub tui: bool,
+    pub net: Option<NetRole>,
+    pub variant: Variant,
+    pub players: u8,
+    pub first: Tile,
+    pub ai: Option<Tile>,
+    pub difficulty: Difficulty,
+    pub seed: Option<u64>,
+    pub names: Option<(String, String)>,
+    pub target: Option<u32>,
+    pub turn_time: Option<Duration>,
+    pub on_timeout: OnTimeout,
+}
+
+// Which end of a network game this side is, and the address to use
+#[derive(PartialEq, Eq, Debug)]
+pub enum NetRole {
+    Host(String),
+    Connect(String),
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum ConfigError {
+    UnknownOption(String),
+    InvalidValue { option: &'static str, value: String },
+    Conflict(&'static str, &'static str),
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            Self::UnknownOption(arg) => write!(f, "Unknown option '{arg}'"),
+            Self::InvalidValue { option, value } => {
+                write!(f, "Invalid value '{value}' for --{option}")
+            }
+            Self::Conflict(a, b) => write!(f, "--{a} can't be combined with --{b}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            size: 3,
+            color: true,
+            bell: true,
+            labels: false,
+            color_blind: false,
+            clear: false,
+            keypad: false,
+            tutorial: false,
+            moves_file: None,
+            auto_open: 0,
+            tui: false,
+            net: None,
+            variant: Variant::Normal,
+            players: 2,
+            first: Tile::X,
+            ai: None,
+            difficulty: Difficulty::Hard,
+            seed: None,
+            names: None,
+            target: None,
+            turn_time: None,
+            on_timeout: OnTimeout::Forfeit,
+        }
+    }
+}
+
+impl Config {
+    pub fn from_args<I, S>(args: I) -> Result<Self, ConfigError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut config = Self::default();
+        for arg in args {
+            let arg = arg.as_ref();
+            let (option, value) = match arg.strip_prefix("--") {
+                Some(option) => option.split_once('=').unwrap_or((option, "")),
+                None => return Err(ConfigError::UnknownOption(arg.to_string())),
+            };
+            let invalid = |option| ConfigError::InvalidValue {
+                option,
+                value: value.to_string(),
+            };
+            match option {
+                "size" => {
+                    config.size = value
+                        .parse()
+                        .ok()
+                        .filter(|size| (3..=15).contains(size))
+                        .ok_or_else(|| invalid("size"))?;
+                }
+                "no-color" if value.is_empty() => config.color = false,
+                "no-bell" if value.is_empty() => config.bell = false,
+                "labels" if value.is_empty() => config.labels = true,
+                "cb" if value.is_empty() => config.color_blind = true,
+                "clear" if value.is_empty() => config.clear = true,
+                "keypad" if value.is_empty() => config.keypad = true,
+                "tutorial" if value.is_empty() => config.tutorial = true,
+                "moves-file" if !value.is_empty() => {
+                    config.moves_file = Some(PathBuf::from(value));
+                }
+                "auto-open" => {
+                    config.auto_open = value.parse().map_err(|_| invalid("auto-open"))?;
+                }
+                "tui" if value.is_empty() && cfg!(feature = "tui") => config.tui = true,
+                "host" | "connect" if !value.is_empty() && cfg!(feature = "net") => {
+                    if config.net.is_some() {
+                        return Err(ConfigError::Conflict("host", "connect"));
+                    }
+                    config.net = Some(if option == "host" {
+                        NetRole::Host(value.to_string())
+                    } else {
+                        NetRole::Connect(value.to_string())
+                    });
+                }
+                "misere" | "wild" if value.is_empty() => {
+                    let variant = if option == "wild" {
+                        Variant::Wild
+                    } else {
+                        Variant::Misere
+                    };
+                    if config.variant != Variant::Normal && config.variant != variant {
+                        return Err(ConfigError::Conflict("misere", "wild"));
+                    }
+                    config.variant = variant;
+                }
+                "first" => {
+                    config.first = match value {
+                        "x" | "X" => Tile::X,
+                        "o" | "O" => Tile::O,
+                        _ => return Err(invalid("first")),
+                    };
+                }
+                "players" => {
+                    config.players = match value {
+                        "2" => 2,
+                        "3" => 3,
+                        _ => return Err(invalid("players")),
+                    };
+                }
+                "ai" => {
+                    config.ai = match value {
+                        "" | "o" | "O" => Some(Tile::O),
+                        "x" | "X" => Some(Tile::X),
+                        "none" => None,
+                        _ => return Err(invalid("ai")),
+                    };
+                }
+                "difficulty" => {
+                    config.difficulty =
+                        parse_difficulty(value).ok_or_else(|| invalid("difficulty"))?;
+                }
+                "seed" => {
+                    config.seed = Some(value.parse().map_err(|_| invalid("seed"))?);
+                }
+                "names" => {
+                    let (x_name, o_name) = value.split_once(',').ok_or_else(|| invalid("names"))?;
+                    config.names = Some((x_name.to_string(), o_name.to_string()));
+                }
+                "match" => {
+                    let target = value
+                        .parse()
+                        .ok()
+                        .filter(|&target| target > 0)
+                        .ok_or_else(|| invalid("match"))?;
+                    config.target = Some(target);
+                }
+                "turn-time" => {
+                    let seconds = value
+                        .parse()
+                        .ok()
+                        .filter(|&seconds| seconds > 0)
+                        .ok_or_else(|| invalid("turn-time"))?;
+                    config.turn_time = Some(Duration::from_secs(seconds));
+                }
+                "on-timeout" => {
+                    config.on_timeout = match value {
+                        "forfeit" => OnTimeout::Forfeit,
+                        "random" => OnTimeout::RandomMove,
+                        _ => return Err(invalid("on-timeout")),
+                    };
+                }
+                _ => return Err(ConfigError::UnknownOption(arg.to_string())),
+            }
+        }
+        // The search only plays each side's own mark, so it can't judge Wild positions
+        if config.variant == Variant::Wild {
+            if config.ai.is_some() {
+                return Err(ConfigError::Conflict("wild", "ai"));
+            }
+            if config.auto_open > 0 {
+                return Err(ConfigError::Conflict("wild", "auto-open"));
+            }
+        }
+        // The computer searches the whole game tree, which only finishes quickly on 3x3
+        if config.size > 3 {
+            if config.ai.is_some() {
+                return Err(ConfigError::Conflict("ai", "size"));
+            }
+            if config.auto_open > 0 {
+                return Err(ConfigError::Conflict("auto-open", "size"));
+            }
+        }
+        if config.keypad && config.size != 3 {
+            return Err(ConfigError::Conflict("keypad", "size"));
+        }
+        if config.keypad && config.variant == Variant::Wild {
+            return Err(ConfigError::Conflict("keypad", "wild"));
+        }
+        if let Some(role) = &config.net {
+            let option = match role {
+                NetRole::Host(_) => "host",
+                NetRole::Connect(_) => "connect",
+            };
+            let conflict = [
+                ("ai", config.ai.is_some()),
+                ("misere", config.variant == Variant::Misere),
+                ("wild", config.variant == Variant::Wild),
+                ("first", config.first != Tile::X),
+                ("players=3", config.players == 3),
+                ("tui", config.tui),
+                ("moves-file", config.moves_file.is_some()),
+                ("auto-open", config.auto_open > 0),
+            ]
+            .into_iter()
+            .find_map(|(other, set)| set.then_some(other));
+            if let Some(other) = conflict {
+                return Err(ConfigError::Conflict(option, other));
+            }
+        }
+        if config.players == 3 {
+            let conflict = match config.variant {
+                Variant::Misere => Some("misere"),
+                Variant::Wild => Some("wild"),
+                Variant::Normal if config.ai.is_some() => Some("ai"),
+                Variant::Normal if config.target.is_some() => Some("match"),
+                Variant::Normal => None,
+            };
+            if let Some(option) = conflict {
+                return Err(ConfigError::Conflict("players=3", option));
+            }
+        }
+        Ok(config)
+    }
+}
+
+pub fn parse_difficulty(value: &str) -> Option<Difficulty> {
+    match value {
+        "easy" => Some(Difficulty::Easy),
+        "medium" => Some(Difficulty::Medium),
+        "hard" => Some(Difficulty::Hard),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults() {
+        assert_eq!(
+            Config::from_args(Vec::<String>::new()),
+            Ok(Config::default())
+        );
+    }
+
+    #[test]
+    fn test_args_map_to_config() {
+        let config = Config::from_args([
+            "--size=3",
+            "--no-color",
+            "--no-bell",
+            "--labels",
+            "--cb",
+            "--clear",
+            "--tutorial",
+            "--moves-file=demo.txt",
+            "--auto-open=2",
+            "--misere",
+            "--first=o",
+            "--ai=x",
+            "--difficulty=easy",
+            "--seed=42",
+            "--names=Alice,Bob",
+            "--match=3",
+            "--turn-time=10",
+            "--on-timeout=random",
+        ])
+        .unwrap();
+        assert_eq!(
+            config,
+            Config {
+                size: 3,
+                color: false,
+                bell: false,
+                labels: true,
+                color_blind: true,
+                clear: true,
+                keypad: false,
+                tutorial: true,
+                moves_file: Some(PathBuf::from("demo.txt")),
+                auto_open: 2,
+                tui: false,
+                net: None,
+                variant: Variant::Misere,
+                players: 2,
+                first: Tile::O,
+                ai: Some(Tile::X),
+                difficulty: Difficulty::Easy,
+                seed: Some(42),
+                names: Some(("Alice".to_string(), "Bob".to_string())),
+                target: Some(3),
+                turn_time: Some(Duration::from_secs(10)),
+                on_timeout: OnTimeout::RandomMove,
+            }
+        );
+        assert_eq!(Config::from_args(["--size=15"]).unwrap().size, 15);
+        assert_eq!(Config::from_args(["--ai"]).unwrap().ai, Some(Tile::O));
+        assert_eq!(Config::from_args(["--ai=none"]).unwrap().ai, None);
+        assert_eq!(Config::from_args(["--players=3"]).unwrap().players, 3);
+        assert_eq!(
+            Config::from_args(["--tui"]).map(|config| config.tui),
+            if cfg!(feature = "tui") {
+                Ok(true)
+            } else {
+                Err(ConfigError::UnknownOption("--tui".to_string()))
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        assert_eq!(
+            Config::from_args(["--ai=both"]),
+            Err(ConfigError::InvalidValue {
+                option: "ai",
+                value: "both".to_string()
+            })
+        );
+        assert!(Config::from_args(["--size=2"]).is_err());
+        assert!(Config::from_args(["--size=many"]).is_err());
+        assert!(Config::from_args(["--difficulty=impossible"]).is_err());
+        assert!(Config::from_args(["--turn-time=0"]).is_err());
+        assert_eq!(
+            Config::from_args(["--match=0"]),
+            Err(ConfigError::InvalidValue {
+                option: "match",
+                value: "0".to_string()
+            })
+        );
+        assert!(Config::from_args(["--on-timeout=pass"]).is_err());
+        assert!(Config::from_args(["--auto-open=-1"]).is_err());
+        assert_eq!(
+            Config::from_args(["--wild", "--misere"]),
+            Err(ConfigError::Conflict("misere", "wild"))
+        );
+        assert_eq!(
+            Config::from_args(["--ai", "--wild"]),
+            Err(ConfigError::Conflict("wild", "ai"))
+        );
+        assert_eq!(
+            Config::from_args(["--wild", "--auto-open=2"]),
+            Err(ConfigError::Conflict("wild", "auto-open"))
+        );
+        assert!(Config::from_args(["--players=4"]).is_err());
+        assert_eq!(
+            Config::from_args(["--size=4", "--ai"]),
+            Err(ConfigError::Conflict("ai", "size"))
+        );
+        assert_eq!(
+            Config::from_args(["--auto-open=2", "--size=5"]),
+            Err(ConfigError::Conflict("auto-open", "size"))
+        );
+        assert!(Config::from_args(["--keypad"]).unwrap().keypad);
+        assert_eq!(
+            Config::from_args(["--keypad", "--size=4"]),
+            Err(ConfigError::Conflict("keypad", "size"))
+        );
+        if cfg!(feature = "net") {
+            assert_eq!(
+                Config::from_args(["--host=0.0.0.0:7878"]).unwrap().net,
+                Some(NetRole::Host("0.0.0.0:7878".to_string()))
+            );
+            assert_eq!(
+                Config::from_args(["--connect=localhost:7878", "--ai"]),
+                Err(ConfigError::Conflict("connect", "ai"))
+            );
+            assert_eq!(
+                Config::from_args(["--host=:7878", "--connect=localhost:7878"]),
+                Err(ConfigError::Conflict("host", "connect"))
+            );
+        } else {
+            assert!(Config::from_args(["--host=0.0.0.0:7878"]).is_err());
+        }
+        assert_eq!(
+            Config::from_args(["--players=3", "--ai"]),
+            Err(ConfigError::Conflict("players=3", "ai"))
+        );
+        assert_eq!(
+            Config::from_args(["--fast"]),
+            Err(ConfigError::UnknownOption("--fast".to_string()))
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{GameState, Tile};
+use core::fmt::{Debug, Formatter};
+
+// Told about everything that happens in a game, for telemetry or relaying moves
+// elsewhere. Both methods do nothing unless overridden
+pub trait GameObserver: Send {
+    fn on_move(&mut self, _index: usize, _tile: Tile) {}
+    fn on_end(&mut self, _state: GameState) {}
+}
+
+pub(crate) type OnEnd = Box<dyn FnMut(&GameState) + Send>;
+
+// Callbacks a consumer hangs on a Game. They aren't part of the position, so a
+// clone starts without them (the AI's search clones games and mustn't fire them)
+// and they're ignored when comparing games
+#[derive(Default)]
+pub(crate) struct Hooks {
+    pub on_end: Option<OnEnd>,
+    pub observers: Vec<Box<dyn GameObserver>>,
+}
+
+impl Hooks {
+    pub fn moved(&mut self, index: usize, tile: Tile) {
+        for observer in &mut self.observers {
+            observer.on_move(index, tile);
+        }
+    }
+
+    pub fn ended(&mut self, state: GameState) {
+        if let Some(on_end) = &mut self.on_end {
+            on_end(&state);
+        }
+        for observer in &mut self.observers {
+            observer.on_end(state);
+        }
+    }
+}
+
+impl Clone for Hooks {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl PartialEq for Hooks {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Hooks {}
+
+impl Debug for Hooks {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        f.debug_struct("Hooks")
+            .field("on_end", &self.on_end.is_some())
+            .field("observers", &self.observers.len())
+            .finish()
+    }
+}
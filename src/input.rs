@@ -0,0 +1,238 @@
+use crate::Tile;
+use core::fmt::{Display, Formatter};
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum InputError {
+    Invalid(String),
+    NoNumber(String),
+    OutOfRange,
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            Self::Invalid(input) => write!(f, "Couldn't read '{input}' as a move"),
+            Self::NoNumber(input) => write!(f, "There's no square number in '{input}'"),
+            Self::OutOfRange => write!(f, "That position is not on the board"),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+// Accepts a square index ("4") or 0-based "row,col" / "row col" coordinates. Words
+// before the number, as in "move 4", are skipped, but anything after it is an error.
+// Any whitespace separates tokens, so a stray '\r' from CRLF input never ends up in one
+pub fn parse_move(s: &str, width: usize) -> Result<usize, InputError> {
+    let s = s.trim();
+    let invalid = || InputError::Invalid(s.to_string());
+    let parts: Vec<&str> = s
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .collect();
+    let Some(start) = parts.iter().position(|part| part.parse::<usize>().is_ok()) else {
+        return Err(InputError::NoNumber(s.to_string()));
+    };
+    match parts[start..] {
+        [index] => index.parse().map_err(|_| invalid()),
+        [row, col] => {
+            let row: usize = row.parse().map_err(|_| invalid())?;
+            let col: usize = col.parse().map_err(|_| invalid())?;
+            if col >= width {
+                return Err(InputError::OutOfRange);
+            }
+            row.checked_mul(width)
+                .and_then(|start| start.checked_add(col))
+                .ok_or(InputError::OutOfRange)
+        }
+        _ => Err(invalid()),
+    }
+}
+
+// A move followed by the symbol to place, such as "4 O" or "1,1 x"
+pub fn parse_wild_move(s: &str, width: usize) -> Result<(usize, Tile), InputError> {
+    let s = s.trim();
+    let invalid = || InputError::Invalid(s.to_string());
+    let (square, symbol) = s.rsplit_once([',', ' ']).ok_or_else(invalid)?;
+    let symbol = match symbol.parse() {
+        Ok(Tile::Empty) | Err(_) => return Err(invalid()),
+        Ok(symbol) => symbol,
+    };
+    Ok((parse_move(square, width)?, symbol))
+}
+
+// Keypad digits laid over a 3x3 board: 7 8 9 on the top row and 1 2 3 on the bottom
+pub const fn keypad_to_index(digit: u8) -> Option<usize> {
+    match digit {
+        1..=9 => {
+            let digit = (digit - 1) as usize;
+            Some((2 - digit / 3) * 3 + digit % 3)
+        }
+        _ => None,
+    }
+}
+
+// "y"/"yes" or "n"/"no" in any case, for questions like "Play again?"
+pub fn parse_yes_no(s: &str) -> Option<bool> {
+    match s.trim().to_lowercase().as_str() {
+        "y" | "yes" => Some(true),
+        "n" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum Command {
+    Move(usize),
+    // A square and the symbol to put there, for the wild variant
+    Place(usize, Tile),
+    Undo,
+    Redo,
+    Hint,
+    Resign,
+    OfferDraw,
+    AcceptDraw,
+    Quit,
+}
+
+pub fn parse_command(s: &str, width: usize) -> Result<Command, InputError> {
+    match s.trim().to_lowercase().as_str() {
+        "undo" => Ok(Command::Undo),
+        "redo" => Ok(Command::Redo),
+        "hint" => Ok(Command::Hint),
+        "resign" => Ok(Command::Resign),
+        "draw" => Ok(Command::OfferDraw),
+        "accept" => Ok(Command::AcceptDraw),
+        "q" | "quit" | "exit" => Ok(Command::Quit),
+        _ => parse_move(s, width).map(Command::Move).or_else(|err| {
+            parse_wild_move(s, width)
+                .map(|(index, symbol)| Command::Place(index, symbol))
+                .map_err(|_| err)
+        }),
+    }
+}
+
+// parse_command for a 3x3 board where single digits are keypad keys
+pub fn parse_keypad_command(s: &str) -> Result<Command, InputError> {
+    match s.trim().parse::<u8>() {
+        Ok(digit) => keypad_to_index(digit)
+            .map(Command::Move)
+            .ok_or(InputError::OutOfRange),
+        Err(_) => parse_command(s, 3),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_index() {
+        assert_eq!(parse_move("4", 3), Ok(4));
+    }
+
+    #[test]
+    fn test_parse_coordinates() {
+        assert_eq!(parse_move("1,1", 3), Ok(4));
+        assert_eq!(parse_move("1 1", 3), Ok(4));
+        assert_eq!(parse_move("2, 0", 3), Ok(6));
+        assert_eq!(parse_move("  4 ", 3), Ok(4));
+        assert_eq!(parse_move("move 4", 3), Ok(4));
+        assert_eq!(parse_move("play 1,1", 3), Ok(4));
+        assert_eq!(parse_move("0,3", 3), Err(InputError::OutOfRange));
+    }
+
+    #[test]
+    fn test_parse_crlf_input() {
+        assert_eq!(parse_move("4\r\n", 3), Ok(4));
+        assert_eq!(parse_move("1,2\r", 3), Ok(5));
+        assert_eq!(parse_move("1\r 2\r\n", 3), Ok(5));
+        assert_eq!(parse_command("undo\r\n", 3), Ok(Command::Undo));
+        assert_eq!(parse_command("4 O\r\n", 3), Ok(Command::Place(4, Tile::O)));
+        assert_eq!(parse_keypad_command("7\r\n"), Ok(Command::Move(0)));
+    }
+
+    #[test]
+    fn test_parse_rejects_garbage() {
+        assert_eq!(parse_move("", 3), Err(InputError::NoNumber(String::new())));
+        assert_eq!(
+            parse_move("a,b", 3),
+            Err(InputError::NoNumber("a,b".to_string()))
+        );
+        assert_eq!(
+            parse_move("abc", 3),
+            Err(InputError::NoNumber("abc".to_string()))
+        );
+        assert_eq!(
+            parse_move("4 please", 3),
+            Err(InputError::Invalid("4 please".to_string()))
+        );
+        assert_eq!(
+            parse_move("1,2,3", 3),
+            Err(InputError::Invalid("1,2,3".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_wild_move() {
+        assert_eq!(parse_wild_move("4 O", 3), Ok((4, Tile::O)));
+        assert_eq!(parse_wild_move("1,1 x", 3), Ok((4, Tile::X)));
+        assert_eq!(parse_wild_move("2 0 X", 3), Ok((6, Tile::X)));
+        assert_eq!(
+            parse_wild_move("4", 3),
+            Err(InputError::Invalid("4".to_string()))
+        );
+        assert_eq!(
+            parse_wild_move("4 .", 3),
+            Err(InputError::Invalid("4 .".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_keypad_to_index() {
+        assert_eq!(keypad_to_index(7), Some(0));
+        assert_eq!(keypad_to_index(9), Some(2));
+        assert_eq!(keypad_to_index(5), Some(4));
+        assert_eq!(keypad_to_index(1), Some(6));
+        assert_eq!(keypad_to_index(3), Some(8));
+        assert_eq!(keypad_to_index(0), None);
+        assert_eq!(parse_keypad_command("7"), Ok(Command::Move(0)));
+        assert_eq!(parse_keypad_command("0"), Err(InputError::OutOfRange));
+        assert_eq!(parse_keypad_command("2,0"), Ok(Command::Move(6)));
+        assert_eq!(parse_keypad_command("undo"), Ok(Command::Undo));
+    }
+
+    #[test]
+    fn test_parse_yes_no() {
+        for yes in ["y", "yes", "Y", "YES", " Yes\n"] {
+            assert_eq!(parse_yes_no(yes), Some(true));
+        }
+        for no in ["n", "no", "N", "No"] {
+            assert_eq!(parse_yes_no(no), Some(false));
+        }
+        for other in ["", "yep", "nope", "ye s", "1"] {
+            assert_eq!(parse_yes_no(other), None);
+        }
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(parse_command("4", 3), Ok(Command::Move(4)));
+        assert_eq!(parse_command("1,1\n", 3), Ok(Command::Move(4)));
+        assert_eq!(parse_command("4 X", 3), Ok(Command::Place(4, Tile::X)));
+        assert_eq!(parse_command("undo", 3), Ok(Command::Undo));
+        assert_eq!(parse_command("redo", 3), Ok(Command::Redo));
+        assert_eq!(parse_command(" hint ", 3), Ok(Command::Hint));
+        assert_eq!(parse_command("Resign", 3), Ok(Command::Resign));
+        assert_eq!(parse_command("draw", 3), Ok(Command::OfferDraw));
+        assert_eq!(parse_command("accept", 3), Ok(Command::AcceptDraw));
+        for quit in ["q", "quit", "exit", "QUIT"] {
+            assert_eq!(parse_command(quit, 3), Ok(Command::Quit));
+        }
+        assert_eq!(parse_command("0,3", 3), Err(InputError::OutOfRange));
+        assert_eq!(
+            parse_command("leave", 3),
+            Err(InputError::NoNumber("leave".to_string()))
+        );
+    }
+}
@@ -0,0 +1,2249 @@
+// Tic-Tac-Toe game in Rust
+
+pub mod ai;
+mod hooks;
+pub mod input;
+#[cfg(feature = "net")]
+pub mod net;
+mod notation;
+mod render;
+mod scoreboard;
+mod view;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use hooks::GameObserver;
+pub use notation::{parse_move_log, ParseError};
+pub use render::Theme;
+pub use scoreboard::Scoreboard;
+pub use view::GameView;
+
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+
+// Index triples of the classic 3x3 board's rows, columns and diagonals
+pub const WIN_LINES: [[usize; 3]; 8] = [
+    [0, 1, 2],
+    [3, 4, 5],
+    [6, 7, 8],
+    [0, 3, 6],
+    [1, 4, 7],
+    [2, 5, 8],
+    [0, 4, 8],
+    [2, 4, 6],
+];
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Tile {
+    X,
+    O,
+    // The third player's mark, only used in three-player games
+    Y,
+    #[default]
+    Empty,
+}
+
+impl Tile {
+    // The other player's mark in a two-player game. Y and Empty have no single
+    // opponent and give Empty
+    #[must_use]
+    pub const fn opponent(self) -> Self {
+        match self {
+            Self::X => Self::O,
+            Self::O => Self::X,
+            Self::Y | Self::Empty => Self::Empty,
+        }
+    }
+
+    pub const fn is_empty(self) -> bool {
+        matches!(self, Self::Empty)
+    }
+
+    pub const fn is_occupied(self) -> bool {
+        !self.is_empty()
+    }
+}
+
+impl Display for Tile {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        // {:#} shows empty squares as a visible dash
+        let symbol = match self {
+            Self::X => 'X',
+            Self::O => 'O',
+            Self::Y => 'Y',
+            Self::Empty if f.alternate() => '-',
+            Self::Empty => ' ',
+        };
+        write!(f, "{symbol}")
+    }
+}
+
+impl FromStr for Tile {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "X" | "x" => Ok(Self::X),
+            "O" | "o" | "0" => Ok(Self::O),
+            "Y" | "y" => Ok(Self::Y),
+            " " | "." => Ok(Self::Empty),
+            _ => Err(ParseError::InvalidTile(s.to_string())),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Row {
+    pub tiles: Vec<Tile>,
+}
+
+impl Row {
+    pub fn new(size: usize) -> Self {
+        Self {
+            tiles: vec![Tile::default(); size],
+        }
+    }
+}
+
+pub struct Diagonal<'a> {
+    pub tiles: Vec<&'a Tile>,
+}
+
+pub trait Completable {
+    fn is_complete(&self, tile: Tile) -> bool;
+    fn has_run(&self, tile: Tile, k: usize) -> bool;
+}
+
+impl Completable for [Tile] {
+    fn is_complete(&self, tile: Tile) -> bool {
+        self.iter().all(|t| *t == tile)
+    }
+
+    fn has_run(&self, tile: Tile, k: usize) -> bool {
+        self.windows(k).any(|window| window.is_complete(tile))
+    }
+}
+
+impl Completable for Row {
+    fn is_complete(&self, tile: Tile) -> bool {
+        self.tiles.is_complete(tile)
+    }
+
+    fn has_run(&self, tile: Tile, k: usize) -> bool {
+        self.tiles.has_run(tile, k)
+    }
+}
+
+impl Completable for Diagonal<'_> {
+    fn is_complete(&self, tile: Tile) -> bool {
+        self.tiles.iter().all(|&t| *t == tile)
+    }
+
+    fn has_run(&self, tile: Tile, k: usize) -> bool {
+        self.tiles
+            .windows(k)
+            .any(|window| window.iter().all(|&t| *t == tile))
+    }
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum MoveError {
+    OutOfBounds,
+    Occupied,
+    GameOver,
+    WrongSymbol,
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            Self::OutOfBounds => write!(f, "That square is not on the board"),
+            Self::Occupied => write!(f, "That square is already taken"),
+            Self::GameOver => write!(f, "The game is already over"),
+            Self::WrongSymbol => write!(f, "You can't place that symbol"),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum WinningLine {
+    Row(usize),
+    Col(usize),
+    Diagonal,
+    AntiDiagonal,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum CellKind {
+    Center,
+    Corner,
+    Edge,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameState {
+    InProgress,
+    Win(Tile),
+    Draw,
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Variant {
+    #[default]
+    Normal,
+    // Completing a line loses
+    Misere,
+    // Either player may place X or O, and whoever completes a line wins
+    Wild,
+}
+
+// Why a game ended before the board decided it
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum EndReason {
+    Forfeit,
+    Resignation,
+    Agreement,
+}
+
+impl Variant {
+    // Who wins once `mover` leaves `completed` owning a full line
+    const fn winner(self, completed: Tile, mover: Tile) -> Tile {
+        match (self, completed) {
+            (Self::Misere, tile) => tile.opponent(),
+            (Self::Wild, _) => mover,
+            (Self::Normal, tile) => tile,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Game {
+    board: Vec<Row>,
+    width: usize,
+    height: usize,
+    k: usize,
+    player: Tile,
+    starter: Tile,
+    turn: u8,
+    state: GameState,
+    // Set when a player forfeits or resigns or a draw is agreed, so the result
+    // needn't follow from the board
+    ended_by: Option<EndReason>,
+    history: Vec<usize>,
+    undone: Vec<(usize, Tile)>,
+    names: Option<[String; 2]>,
+    variant: Variant,
+    players: u8,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    theme: Theme,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    hooks: hooks::Hooks,
+}
+
+impl Game {
+    pub fn new() -> Self {
+        Self::with_size(3)
+    }
+
+    pub fn with_size(size: usize) -> Self {
+        Self::with_rules(size, size, size)
+    }
+
+    pub fn with_rules(width: usize, height: usize, k: usize) -> Self {
+        assert!(
+            width > 0 && height > 0 && width * height <= usize::from(u8::MAX),
+            "Unsupported board size {width}x{height}"
+        );
+        assert!(
+            k > 0 && k <= width.max(height),
+            "Unsupported win length {k} for a {width}x{height} board"
+        );
+        Self {
+            board: (0..height).map(|_| Row::new(width)).collect(),
+            width,
+            height,
+            k,
+            player: Tile::X,
+            starter: Tile::X,
+            turn: 0,
+            state: GameState::InProgress,
+            ended_by: None,
+            history: Vec::new(),
+            undone: Vec::new(),
+            names: None,
+            variant: Variant::Normal,
+            players: 2,
+            theme: Theme::default(),
+            hooks: hooks::Hooks::default(),
+        }
+    }
+
+    pub fn with_names(x_name: impl Into<String>, o_name: impl Into<String>) -> Self {
+        Self {
+            names: Some([x_name.into(), o_name.into()]),
+            ..Self::new()
+        }
+    }
+
+    pub fn new_with_starter(first: Tile) -> Self {
+        let mut game = Self::new();
+        game.set_starter(first);
+        game
+    }
+
+    pub fn from_moves(moves: &[usize]) -> Result<Self, MoveError> {
+        let mut game = Self::new();
+        for &index in moves {
+            game.play(index)?;
+        }
+        Ok(game)
+    }
+
+    // Starts a new round on the same board, keeping names and theme
+    pub fn reset(&mut self) {
+        for row in &mut self.board {
+            row.tiles.fill(Tile::Empty);
+        }
+        self.player = self.starter;
+        self.turn = 0;
+        self.state = GameState::InProgress;
+        self.ended_by = None;
+        self.history.clear();
+        self.undone.clear();
+    }
+
+    pub fn play(&mut self, index: usize) -> Result<(), MoveError> {
+        self.place(index, self.player)
+    }
+
+    pub fn play_at(&mut self, row: usize, col: usize) -> Result<(), MoveError> {
+        if row >= self.height || col >= self.width {
+            return Err(MoveError::OutOfBounds);
+        }
+        self.play(row * self.width + col)
+    }
+
+    // Places `symbol` for the player on move, which must be their own mark unless
+    // the variant is Wild
+    pub fn place(&mut self, index: usize, symbol: Tile) -> Result<(), MoveError> {
+        if !self.marks().contains(&symbol)
+            || (self.variant != Variant::Wild && symbol != self.player)
+        {
+            return Err(MoveError::WrongSymbol);
+        }
+        self.apply(index, symbol)?;
+        self.undone.clear();
+        Ok(())
+    }
+
+    fn apply(&mut self, index: usize, symbol: Tile) -> Result<(), MoveError> {
+        if self.game_over() {
+            return Err(MoveError::GameOver);
+        }
+        if index >= self.cell_count() {
+            return Err(MoveError::OutOfBounds);
+        }
+        let row = index / self.width;
+        let col = index % self.width;
+
+        if self.board[row].tiles[col].is_occupied() {
+            return Err(MoveError::Occupied);
+        }
+        self.board[row].tiles[col] = symbol;
+        self.turn += 1;
+        self.history.push(index);
+        self.hooks.moved(index, symbol);
+
+        let mover = self.player;
+        self.player = self.next_player(mover);
+        log::debug!("{symbol} played {index}, {} to move", self.player);
+        if let Some(completed) = self.winner_on_board() {
+            let winner = self.variant.winner(completed, mover);
+            self.finish(GameState::Win(winner));
+            log::info!("{winner} wins after {} moves", self.turn);
+        } else if self.is_tie() || self.is_forced_draw() {
+            self.finish(GameState::Draw);
+            log::info!("Draw after {} moves", self.turn);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a game always serializes")
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn from_json(s: &str) -> Result<Self, serde_json::Error> {
+        use serde::de::Error;
+        let game: Self = serde_json::from_str(s)?;
+        if !game.well_formed() {
+            return Err(serde_json::Error::custom(
+                "the board doesn't match its size",
+            ));
+        }
+        if !game.is_valid() {
+            return Err(serde_json::Error::custom("the position can't happen"));
+        }
+        Ok(game)
+    }
+
+    // Whether the fields describe a board with_rules() could have built, so indexing
+    // into it can't go out of bounds and undo() and redo() have real moves to
+    // replay. Only a deserialized game can fail this
+    #[cfg(feature = "serde")]
+    fn well_formed(&self) -> bool {
+        let cells = self.width * self.height;
+        self.width > 0
+            && self.height > 0
+            && cells <= usize::from(u8::MAX)
+            && self.k > 0
+            && self.k <= self.width.max(self.height)
+            && self.board.len() == self.height
+            && self.board.iter().all(|row| row.tiles.len() == self.width)
+            && (2..=3).contains(&self.players)
+            && (self.players == 2 || self.variant == Variant::Normal)
+            && self.history.len() == usize::from(self.turn)
+            && matches!(self.starter, Tile::X | Tile::O)
+            && self.history.iter().enumerate().all(|(i, &index)| {
+                index < cells
+                    && self.tile_at(index).is_occupied()
+                    && !self.history[..i].contains(&index)
+            })
+            && self.undone.iter().enumerate().all(|(i, &(index, mark))| {
+                index < cells
+                    && self.tile_at(index).is_empty()
+                    && self.marks().contains(&mark)
+                    && self.undone[..i].iter().all(|&(other, _)| other != index)
+            })
+            && (self.undone.is_empty() || self.state == GameState::InProgress)
+    }
+
+    pub fn legal_moves(&self) -> impl Iterator<Item = usize> + '_ {
+        let cells = if self.game_over() {
+            0
+        } else {
+            self.cell_count()
+        };
+        (0..cells).filter(|&index| self.tile_at(index).is_empty())
+    }
+
+    // Empty cells as 0-based (row, col) pairs in row-major order. Unlike legal_moves()
+    // these are still listed once the game is over
+    pub fn empty_coords(&self) -> Vec<(usize, usize)> {
+        (0..self.cell_count())
+            .filter(|&index| self.tile_at(index).is_empty())
+            .map(|index| (index / self.width, index % self.width))
+            .collect()
+    }
+
+    // Every distinct sequence of moves from here to the end of the game, with no
+    // symmetry folding. This counts by the classic rule, where a game goes on until
+    // someone completes a line or the board fills, even once play() would have
+    // called the draw, so the empty 3x3 board gives the well-known 255,168
+    pub fn count_games(&self) -> u64 {
+        if self.ended_by.is_some() {
+            return 1;
+        }
+        self.clone().count_to_the_end()
+    }
+
+    fn count_to_the_end(&mut self) -> u64 {
+        if self.is_complete() || self.is_tie() {
+            return 1;
+        }
+        let mover = self.player;
+        self.player = self.next_player(mover);
+        let mut games = 0;
+        for index in 0..self.cell_count() {
+            let (row, col) = (index / self.width, index % self.width);
+            if self.board[row].tiles[col].is_empty() {
+                self.board[row].tiles[col] = mover;
+                games += self.count_to_the_end();
+                self.board[row].tiles[col] = Tile::Empty;
+            }
+        }
+        self.player = mover;
+        games
+    }
+
+    // The nth empty square, counting round again past the last one
+    pub fn nth_legal_move(&self, n: usize) -> Option<usize> {
+        let count = self.squares_remaining();
+        if count == 0 {
+            return None;
+        }
+        self.legal_moves().nth(n % count)
+    }
+
+    pub fn hint(&self) -> Option<usize> {
+        (!self.game_over()).then(|| ai::best_move(self, self.player))
+    }
+
+    pub fn ranked_moves(&self, player: Tile) -> Vec<(usize, i32)> {
+        ai::Ai::new().ranked_moves(self, player)
+    }
+
+    pub fn forced_result(&self) -> GameState {
+        ai::Ai::new().forced_result(self)
+    }
+
+    // Whether playing `played` turns a win into a draw or loss, or a draw into a
+    // loss. Winning more slowly than possible doesn't count
+    pub fn is_blunder(&self, played: usize, player: Tile) -> bool {
+        let ranked = self.ranked_moves(player);
+        let outcome = |index: usize| {
+            ranked
+                .iter()
+                .find(|&&(square, _)| square == index)
+                .map(|&(_, score)| score.signum())
+        };
+        match (ranked.first(), outcome(played)) {
+            (Some(&(best, _)), Some(played)) => outcome(best).is_some_and(|best| played < best),
+            _ => false,
+        }
+    }
+
+    pub fn player_label(&self, tile: Tile) -> String {
+        let name = self.names.as_ref().and_then(|[x_name, o_name]| match tile {
+            Tile::X => Some(x_name),
+            Tile::O => Some(o_name),
+            Tile::Y | Tile::Empty => None,
+        });
+        match name {
+            Some(name) => format!("{name} ({tile})"),
+            None => format!("Player {tile}"),
+        }
+    }
+
+    pub fn announcement(&self) -> Option<String> {
+        match self.state {
+            GameState::Win(winner) => Some(format!("{} wins!", self.player_label(winner))),
+            GameState::Draw => Some("It's a tie!".to_string()),
+            GameState::InProgress => None,
+        }
+    }
+
+    // Says how the game ended, e.g. "X wins on the top row"
+    pub fn outcome_message(&self) -> Option<String> {
+        let winner = match self.state {
+            GameState::InProgress => return None,
+            GameState::Draw if self.is_tie() => return Some("Draw — board full".to_string()),
+            GameState::Draw if self.ended_by == Some(EndReason::Agreement) => {
+                return Some("Draw by agreement".to_string())
+            }
+            GameState::Draw => return Some("Draw — no line can be completed".to_string()),
+            GameState::Win(winner) if self.ended_by == Some(EndReason::Resignation) => {
+                return Some(format!("{winner} wins, {} resigned", winner.opponent()))
+            }
+            GameState::Win(winner) => winner,
+        };
+        Some(match (self.winning_line(), self.winner_on_board()) {
+            // Misere, where the line belongs to the loser
+            (Some(line), Some(owner)) if owner != winner => {
+                format!("{winner} wins, {owner} completed {}", self.line_name(line))
+            }
+            (Some(line), _) => format!("{winner} wins on {}", self.line_name(line)),
+            (None, _) => format!("{winner} wins by forfeit"),
+        })
+    }
+
+    fn line_name(&self, line: WinningLine) -> String {
+        let full = self.width == self.height && self.k == self.width;
+        match line {
+            WinningLine::Row(0) => "the top row".to_string(),
+            WinningLine::Row(row) if row == self.height - 1 => "the bottom row".to_string(),
+            WinningLine::Row(1) if self.height == 3 => "the middle row".to_string(),
+            WinningLine::Row(row) => format!("row {row}"),
+            WinningLine::Col(0) => "the left column".to_string(),
+            WinningLine::Col(col) if col == self.width - 1 => "the right column".to_string(),
+            WinningLine::Col(1) if self.width == 3 => "the middle column".to_string(),
+            WinningLine::Col(col) => format!("column {col}"),
+            WinningLine::Diagonal if full => "the main diagonal".to_string(),
+            WinningLine::Diagonal => "a diagonal".to_string(),
+            WinningLine::AntiDiagonal if full => "the anti-diagonal".to_string(),
+            WinningLine::AntiDiagonal => "an anti-diagonal".to_string(),
+        }
+    }
+
+    pub fn set_names(&mut self, x_name: impl Into<String>, o_name: impl Into<String>) {
+        self.names = Some([x_name.into(), o_name.into()]);
+    }
+
+    pub fn set_variant(&mut self, variant: Variant) {
+        assert!(
+            self.players == 2 || variant == Variant::Normal,
+            "{variant:?} needs two players"
+        );
+        self.variant = variant;
+    }
+
+    pub const fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    // Called with the result whenever the game ends, by a move, a forfeit, a
+    // resignation or an agreed draw. Clones of the game don't keep it
+    pub fn set_on_end(&mut self, on_end: impl FnMut(&GameState) + Send + 'static) {
+        self.hooks.on_end = Some(Box::new(on_end));
+    }
+
+    // Observers hear about every move and the end of the game, in the order added.
+    // Like set_on_end(), they stay with this game and not its clones
+    pub fn add_observer(&mut self, observer: impl GameObserver + 'static) {
+        self.hooks.observers.push(Box::new(observer));
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    // Two or three players, taking turns as X, O, then Y. Only Normal rules make
+    // sense with three, since Misere and Wild hand the win to a single opponent
+    pub fn set_players(&mut self, players: u8) {
+        assert!(
+            (2..=3).contains(&players),
+            "Unsupported player count {players}"
+        );
+        assert!(
+            players == 2 || self.variant == Variant::Normal,
+            "{:?} needs two players",
+            self.variant
+        );
+        self.players = players;
+    }
+
+    // Who opens the game, from the next reset if moves have already been played
+    pub fn set_starter(&mut self, first: Tile) {
+        assert!(
+            matches!(first, Tile::X | Tile::O),
+            "Invalid starter {first:?}"
+        );
+        self.starter = first;
+        if self.turn == 0 {
+            self.player = first;
+        }
+    }
+
+    pub const fn starter(&self) -> Tile {
+        self.starter
+    }
+
+    pub const fn players(&self) -> u8 {
+        self.players
+    }
+
+    // The marks in play, in turn order
+    pub fn marks(&self) -> &'static [Tile] {
+        if self.players == 3 {
+            &[Tile::X, Tile::O, Tile::Y]
+        } else {
+            &[Tile::X, Tile::O]
+        }
+    }
+
+    const fn next_player(&self, tile: Tile) -> Tile {
+        match tile {
+            Tile::O if self.players == 3 => Tile::Y,
+            Tile::Y => Tile::X,
+            tile => tile.opponent(),
+        }
+    }
+
+    const fn previous_player(&self, tile: Tile) -> Tile {
+        match tile {
+            Tile::X if self.players == 3 => Tile::Y,
+            Tile::Y => Tile::O,
+            tile => tile.opponent(),
+        }
+    }
+
+    fn finish(&mut self, state: GameState) {
+        self.state = state;
+        self.hooks.ended(state);
+    }
+
+    // A forfeit, resignation or agreement. The moves undone before it can't be
+    // redone past the end of the game, so they go
+    fn end_early(&mut self, reason: EndReason, state: GameState) {
+        self.ended_by = Some(reason);
+        self.undone.clear();
+        self.finish(state);
+    }
+
+    // Ends the game in favour of whoever moved last
+    pub fn forfeit(&mut self) -> Result<(), MoveError> {
+        if self.game_over() {
+            return Err(MoveError::GameOver);
+        }
+        self.end_early(
+            EndReason::Forfeit,
+            GameState::Win(self.previous_player(self.player)),
+        );
+        Ok(())
+    }
+
+    // `who` gives up and their opponent wins, whoever is on move
+    pub fn resign(&mut self, who: Tile) -> Result<(), MoveError> {
+        if self.game_over() {
+            return Err(MoveError::GameOver);
+        }
+        if self.players != 2 || !self.marks().contains(&who) {
+            return Err(MoveError::WrongSymbol);
+        }
+        self.end_early(EndReason::Resignation, GameState::Win(who.opponent()));
+        Ok(())
+    }
+
+    // Both players agreed to call it a draw
+    pub fn agree_draw(&mut self) -> Result<(), MoveError> {
+        if self.game_over() {
+            return Err(MoveError::GameOver);
+        }
+        self.end_early(EndReason::Agreement, GameState::Draw);
+        Ok(())
+    }
+
+    pub fn undo(&mut self) -> Option<usize> {
+        let index = self.history.pop()?;
+        let tile = &mut self.board[index / self.width].tiles[index % self.width];
+        self.undone.push((index, *tile));
+        *tile = Tile::Empty;
+        self.player = self.previous_player(self.player);
+        self.turn -= 1;
+        self.state = GameState::InProgress;
+        self.ended_by = None;
+        Some(index)
+    }
+
+    pub fn redo(&mut self) -> Option<usize> {
+        let (index, symbol) = self.undone.pop()?;
+        self.apply(index, symbol)
+            .expect("undone moves are always replayable");
+        Some(index)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.winner_on_board().is_some()
+    }
+
+    // Whoever owns a completed line, read from the board rather than the turn order
+    pub fn winner_on_board(&self) -> Option<Tile> {
+        self.lines()
+            .find(|line| line[0].is_occupied() && line.is_complete(line[0]))
+            .map(|line| line[0])
+    }
+
+    // The tiles of every line a player could win on
+    pub fn lines(&self) -> impl Iterator<Item = Vec<Tile>> + '_ {
+        self.runs()
+            .into_iter()
+            .map(|run| run.into_iter().map(|index| self.tile_at(index)).collect())
+    }
+
+    pub fn any_row_complete(&self, tile: Tile) -> bool {
+        self.complete_row(tile).is_some()
+    }
+
+    pub fn any_col_complete(&self, tile: Tile) -> bool {
+        self.complete_col(tile).is_some()
+    }
+
+    pub fn any_diagonal_complete(&self, tile: Tile) -> bool {
+        self.complete_diagonal(tile).is_some()
+    }
+
+    pub fn winning_line(&self) -> Option<WinningLine> {
+        self.marks().iter().find_map(|&tile| {
+            self.complete_row(tile)
+                .map(WinningLine::Row)
+                .or_else(|| self.complete_col(tile).map(WinningLine::Col))
+                .or_else(|| self.complete_diagonal(tile))
+        })
+    }
+
+    // Lines `player` could still win on, holding only their marks and empty squares
+    pub fn open_lines(&self, player: Tile) -> usize {
+        self.lines()
+            .filter(|line| line.iter().all(|&t| t == player || t.is_empty()))
+            .count()
+    }
+
+    // Empty squares that would complete a line for `player` straight away
+    pub fn winning_moves(&self, player: Tile) -> Vec<usize> {
+        if self.game_over() {
+            return Vec::new();
+        }
+        let mut moves: Vec<usize> = self
+            .runs()
+            .into_iter()
+            .filter_map(|run| {
+                let mut empty = run.iter().filter(|&&index| self.tile_at(index).is_empty());
+                let square = *empty.next()?;
+                let rest_owned = run
+                    .iter()
+                    .all(|&index| index == square || self.tile_at(index) == player);
+                (empty.next().is_none() && rest_owned).then_some(square)
+            })
+            .collect();
+        moves.sort_unstable();
+        moves.dedup();
+        moves
+    }
+
+    // Empty squares that would leave `player` two or more ways to win next move. The
+    // mark is tried without taking a turn, so either side's forks can be found
+    pub fn forks(&self, player: Tile) -> Vec<usize> {
+        self.legal_moves()
+            .filter(|&index| {
+                let mut next = self.clone();
+                next.board[index / self.width].tiles[index % self.width] = player;
+                next.winning_moves(player).len() >= 2
+            })
+            .collect()
+    }
+
+    // winning_moves() for X and for O, in that order
+    pub fn threats(&self) -> (Vec<usize>, Vec<usize>) {
+        (self.winning_moves(Tile::X), self.winning_moves(Tile::O))
+    }
+
+    pub fn winning_cells(&self) -> Option<Vec<usize>> {
+        self.runs().into_iter().find(|run| {
+            let first = self.tile_at(run[0]);
+            first.is_occupied() && run.iter().all(|&index| self.tile_at(index) == first)
+        })
+    }
+
+    // Index lists of every k-long window across rows, columns, and both diagonals
+    fn runs(&self) -> Vec<Vec<usize>> {
+        if (self.width, self.height, self.k) == (3, 3, 3) {
+            return WIN_LINES.iter().map(|line| line.to_vec()).collect();
+        }
+        self.scan_runs()
+    }
+
+    fn scan_runs(&self) -> Vec<Vec<usize>> {
+        const DIRECTIONS: [(usize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+        let mut runs = Vec::new();
+        for row in 0..self.height {
+            for col in 0..self.width {
+                for (row_step, col_step) in DIRECTIONS {
+                    let run: Option<Vec<usize>> = (0..self.k)
+                        .map(|step| {
+                            let r = row + row_step * step;
+                            let c = col.checked_add_signed(col_step * step.cast_signed())?;
+                            (r < self.height && c < self.width).then_some(r * self.width + c)
+                        })
+                        .collect();
+                    runs.extend(run);
+                }
+            }
+        }
+        runs
+    }
+
+    fn tile_at(&self, index: usize) -> Tile {
+        self.board[index / self.width].tiles[index % self.width]
+    }
+
+    fn complete_row(&self, tile: Tile) -> Option<usize> {
+        self.board.iter().position(|row| row.has_run(tile, self.k))
+    }
+
+    fn complete_col(&self, tile: Tile) -> Option<usize> {
+        let cols = self.cols();
+        cols.iter().position(|col| col.has_run(tile, self.k))
+    }
+
+    fn complete_diagonal(&self, tile: Tile) -> Option<WinningLine> {
+        let has_run = |diags: Vec<Diagonal>| diags.iter().any(|diag| diag.has_run(tile, self.k));
+        if has_run(self.diagonals_along(false)) {
+            Some(WinningLine::Diagonal)
+        } else if has_run(self.diagonals_along(true)) {
+            Some(WinningLine::AntiDiagonal)
+        } else {
+            None
+        }
+    }
+
+    pub fn diagonals(&self) -> [Diagonal<'_>; 2] {
+        let len = self.width.min(self.height);
+        let last = self.width - 1;
+        [
+            Diagonal {
+                tiles: (0..len).map(|i| &self.board[i].tiles[i]).collect(),
+            },
+            Diagonal {
+                tiles: (0..len).map(|i| &self.board[i].tiles[last - i]).collect(),
+            },
+        ]
+    }
+
+    // Every diagonal in both directions that is long enough to hold a winning run
+    pub fn all_diagonals(&self) -> Vec<Diagonal<'_>> {
+        let mut diagonals = self.diagonals_along(false);
+        diagonals.extend(self.diagonals_along(true));
+        diagonals
+    }
+
+    fn diagonals_along(&self, anti: bool) -> Vec<Diagonal<'_>> {
+        let mut diagonals: Vec<Diagonal> = (0..self.width + self.height - 1)
+            .map(|offset| Diagonal {
+                tiles: (0..self.height)
+                    .filter_map(|row| {
+                        let col = if anti {
+                            offset.checked_sub(row)?
+                        } else {
+                            (row + offset).checked_sub(self.height - 1)?
+                        };
+                        self.board[row].tiles.get(col)
+                    })
+                    .collect(),
+            })
+            .collect();
+        diagonals.retain(|diag| diag.tiles.len() >= self.k);
+        diagonals
+    }
+
+    pub fn cols(&self) -> Vec<Row> {
+        (0..self.width)
+            .map(|col| Row {
+                tiles: self.board.iter().map(|row| row.tiles[col]).collect(),
+            })
+            .collect()
+    }
+
+    // Like cols()[col] but borrowing from the board instead of copying
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &Tile> + '_ {
+        assert!(col < self.width, "Column {col} is off the board");
+        self.board.iter().map(move |row| &row.tiles[col])
+    }
+
+    // The main diagonal from the top left, or the anti-diagonal from the top right,
+    // borrowed like diagonals() but without collecting
+    pub fn diagonal(&self, anti: bool) -> impl Iterator<Item = &Tile> + '_ {
+        let last = self.width - 1;
+        (0..self.width.min(self.height)).map(move |i| {
+            let col = if anti { last - i } else { i };
+            &self.board[i].tiles[col]
+        })
+    }
+
+    // Whether both boards hold the same marks, however each game got there. Unlike ==,
+    // the move history, turn count and result don't matter
+    pub fn same_board(&self, other: &Self) -> bool {
+        self.board == other.board
+    }
+
+    pub fn grid(&self) -> Vec<Vec<Tile>> {
+        self.board.iter().map(|row| row.tiles.clone()).collect()
+    }
+
+    // Cells in row-major order, usable as a HashMap/HashSet key. Whose turn it is isn't
+    // part of the key, so pair it with current_player() when that matters
+    pub fn board_key(&self) -> Vec<Tile> {
+        self.board
+            .iter()
+            .flat_map(|row| row.tiles.iter().copied())
+            .collect()
+    }
+
+    // board_key() as a fixed-size array, such as [Tile; 9] for the classic board, or
+    // None when N isn't the number of cells
+    pub fn as_array<const N: usize>(&self) -> Option<[Tile; N]> {
+        self.board_key().try_into().ok()
+    }
+
+    // Smallest of the board's images under every rotation and reflection it allows
+    pub fn canonical(&self) -> Vec<Tile> {
+        let (w, h) = (self.width, self.height);
+        let symmetries = if w == h { 8 } else { 4 };
+        (0..symmetries)
+            .map(|symmetry| {
+                (0..self.cell_count())
+                    .map(|index| {
+                        let (r, c) = (index / w, index % w);
+                        let (r, c) = match symmetry {
+                            0 => (r, c),
+                            1 => (r, w - 1 - c),
+                            2 => (h - 1 - r, c),
+                            3 => (h - 1 - r, w - 1 - c),
+                            4 => (c, r),
+                            5 => (w - 1 - c, h - 1 - r),
+                            6 => (c, h - 1 - r),
+                            _ => (w - 1 - c, r),
+                        };
+                        self.board[r].tiles[c]
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .min()
+            .expect("the identity is always a symmetry")
+    }
+
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    // Moves in the history, so it follows undo and redo. A position loaded from
+    // notation has no history and starts at zero
+    pub fn moves_played(&self) -> usize {
+        self.history.len()
+    }
+
+    // The move number, counting from 1, that filled each square, in row-major order.
+    // Positions loaded from notation have no history, so their squares show None
+    pub fn move_order(&self) -> Vec<Option<u8>> {
+        let mut order = vec![None; self.cell_count()];
+        for (number, &index) in (1..=u8::MAX).zip(&self.history) {
+            order[index] = Some(number);
+        }
+        order
+    }
+
+    pub fn squares_remaining(&self) -> usize {
+        self.legal_moves().count()
+    }
+
+    pub const fn cell_count(&self) -> usize {
+        self.width * self.height
+    }
+
+    // Squares on the border are edges or corners, and everything inside counts as centre
+    pub const fn cell_kind(&self, index: usize) -> Option<CellKind> {
+        if index >= self.cell_count() {
+            return None;
+        }
+        let (row, col) = (index / self.width, index % self.width);
+        let row = row == 0 || row == self.height - 1;
+        let col = col == 0 || col == self.width - 1;
+        Some(match (row, col) {
+            (true, true) => CellKind::Corner,
+            (true, false) | (false, true) => CellKind::Edge,
+            (false, false) => CellKind::Center,
+        })
+    }
+
+    pub fn is_tie(&self) -> bool {
+        let full = self
+            .board
+            .iter()
+            .all(|row| row.tiles.iter().all(|t| t.is_occupied()));
+        full && !self.is_complete()
+    }
+
+    // No run can still be filled by any side with the moves each has left
+    pub fn is_forced_draw(&self) -> bool {
+        let empty = (0..self.cell_count())
+            .filter(|&index| self.tile_at(index).is_empty())
+            .count();
+        let marks = self.marks();
+        let players = marks.len();
+        let on_move = marks.iter().position(|&t| t == self.player).unwrap_or(0);
+        let remaining = |tile: Tile| {
+            // In Wild both players can place either mark
+            if self.variant == Variant::Wild {
+                return empty;
+            }
+            let position = marks.iter().position(|&t| t == tile).unwrap_or(0);
+            let waiting = (position + players - on_move) % players;
+            (empty + players - 1 - waiting) / players
+        };
+        !self.is_complete()
+            && self.runs().iter().all(|run| {
+                let tiles: Vec<Tile> = run.iter().map(|&index| self.tile_at(index)).collect();
+                let open = tiles.iter().filter(|&&t| t.is_empty()).count();
+                marks.iter().all(|&tile| {
+                    tiles.iter().any(|&t| t != tile && t.is_occupied()) || open > remaining(tile)
+                })
+            })
+    }
+
+    // Whose turn it is after `turn` moves, going round the marks from the starter
+    fn expected_player(&self) -> Tile {
+        let marks = self.marks();
+        let first = marks.iter().position(|&t| t == self.starter).unwrap_or(0);
+        marks[(first + usize::from(self.turn)) % marks.len()]
+    }
+
+    // Whether each mark has been played as often as the one after it in turn
+    // order, or once more
+    fn marks_alternate(&self) -> bool {
+        let board = self.board_key();
+        let mut counts: Vec<usize> = self
+            .marks()
+            .iter()
+            .map(|&mark| board.iter().filter(|&&t| t == mark).count())
+            .collect();
+        let first = self.marks().iter().position(|&t| t == self.starter);
+        counts.rotate_left(first.unwrap_or(0));
+        counts.windows(2).all(|pair| pair[0] >= pair[1])
+            && counts[0] <= counts[counts.len() - 1] + 1
+    }
+
+    // Checks that the board could come from real play and the state agrees with it
+    pub fn is_valid(&self) -> bool {
+        let filled = self
+            .board_key()
+            .iter()
+            .filter(|&&t| t.is_occupied())
+            .count();
+        let mut owners: Vec<Tile> = self
+            .lines()
+            .filter(|line| line[0].is_occupied() && line.is_complete(line[0]))
+            .map(|line| line[0])
+            .collect();
+        owners.dedup();
+        // Outside Wild, whoever owns a line made the last move, since the game stops
+        // the moment a line is completed
+        let owner_moved_last = self.variant == Variant::Wild
+            || owners
+                .first()
+                .is_none_or(|&owner| owner == self.previous_player(self.expected_player()));
+        let state_matches = match (self.state, owners.first()) {
+            (GameState::InProgress, owner) => {
+                owner.is_none() && self.ended_by.is_none() && !self.is_tie()
+            }
+            (GameState::Draw, owner) => {
+                owner.is_none()
+                    && (self.ended_by == Some(EndReason::Agreement)
+                        || self.is_tie()
+                        || self.is_forced_draw())
+            }
+            (GameState::Win(winner), Some(&owner)) => {
+                self.marks().contains(&winner)
+                    && (self.variant == Variant::Wild
+                        || winner == self.variant.winner(owner, owner))
+            }
+            (GameState::Win(winner), None) => {
+                matches!(
+                    self.ended_by,
+                    Some(EndReason::Forfeit | EndReason::Resignation)
+                ) && self.marks().contains(&winner)
+            }
+        };
+        usize::from(self.turn) == filled
+            && self.player == self.expected_player()
+            && (self.variant == Variant::Wild || self.marks_alternate())
+            && owners.len() <= 1
+            && owner_moved_last
+            && state_matches
+    }
+
+    pub const fn state(&self) -> GameState {
+        self.state
+    }
+
+    pub const fn game_over(&self) -> bool {
+        !matches!(self.state(), GameState::InProgress)
+    }
+
+    pub const fn current_player(&self) -> Tile {
+        self.player
+    }
+
+    // Marks placed so far, which undo takes back off
+    pub const fn turn(&self) -> u8 {
+        self.turn
+    }
+
+    pub const fn winner(&self) -> Option<Tile> {
+        match self.state {
+            GameState::Win(tile) => Some(tile),
+            GameState::InProgress | GameState::Draw => None,
+        }
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use std::thread::{self, ThreadId};
+
+    // Keeps every record with the thread that logged it, since tests run in parallel
+    struct TestLogger(Mutex<Vec<(ThreadId, log::Level, String)>>);
+
+    impl log::Log for TestLogger {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            let entry = (
+                thread::current().id(),
+                record.level(),
+                record.args().to_string(),
+            );
+            self.0.lock().unwrap().push(entry);
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: TestLogger = TestLogger(Mutex::new(Vec::new()));
+
+    #[test]
+    fn test_moves_are_logged() {
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(log::LevelFilter::Debug);
+        Game::from_moves(&[4, 0, 2, 1, 6]).unwrap();
+        let records: Vec<(log::Level, String)> = LOGGER
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(thread, _, _)| *thread == thread::current().id())
+            .map(|(_, level, message)| (*level, message.clone()))
+            .collect();
+        assert_eq!(records.len(), 6);
+        assert_eq!(
+            records[0],
+            (log::Level::Debug, "X played 4, O to move".to_string())
+        );
+        assert_eq!(
+            records[5],
+            (log::Level::Info, "X wins after 5 moves".to_string())
+        );
+    }
+
+    #[test]
+    fn test_forced_result() {
+        assert_eq!(Game::new().forced_result(), GameState::Draw);
+        let game = play_all(&[0, 3, 1, 4]);
+        assert_eq!(game.forced_result(), GameState::Win(game.current_player()));
+        let game = play_all(&[0, 3, 1, 4, 8]);
+        assert_eq!(game.forced_result(), GameState::Win(Tile::O));
+        let game = play_all(&[0, 3, 1, 4, 2]);
+        assert_eq!(game.forced_result(), GameState::Win(Tile::X));
+    }
+
+    #[test]
+    fn test_is_blunder() {
+        let game = play_all(&[0, 3, 1, 4]);
+        assert!(!game.is_blunder(2, Tile::X));
+        assert!(game.is_blunder(8, Tile::X));
+        let game = Game::new();
+        assert!(!game.is_blunder(1, Tile::X));
+        assert!(!game.is_blunder(9, Tile::X));
+    }
+
+    #[test]
+    fn test_o_moves_first() {
+        let mut game = Game::new_with_starter(Tile::O);
+        assert_eq!(game.current_player(), Tile::O);
+        game.play(4).unwrap();
+        game.play(0).unwrap();
+        assert_eq!(game.tile_at(4), Tile::O);
+        assert_eq!(game.tile_at(0), Tile::X);
+        assert!(game.is_valid());
+
+        for index in [5, 1, 3] {
+            game.play(index).unwrap();
+        }
+        assert_eq!(game.winner(), Some(Tile::O));
+        game.reset();
+        assert_eq!(game.current_player(), Tile::O);
+    }
+
+    #[test]
+    fn test_defaults() {
+        assert_eq!(Tile::default(), Tile::Empty);
+        assert_eq!(Game::default(), Game::new());
+    }
+
+    #[test]
+    fn test_tile_opponent() {
+        assert_eq!(Tile::X.opponent(), Tile::O);
+        assert_eq!(Tile::O.opponent(), Tile::X);
+        assert_eq!(Tile::Empty.opponent(), Tile::Empty);
+    }
+
+    #[test]
+    fn test_tile_is_empty() {
+        assert!(Tile::Empty.is_empty());
+        assert!(!Tile::Empty.is_occupied());
+        for tile in [Tile::X, Tile::O, Tile::Y] {
+            assert!(!tile.is_empty());
+            assert!(tile.is_occupied());
+        }
+    }
+
+    #[test]
+    fn test_tile_from_str() {
+        for s in ["X", "x"] {
+            assert_eq!(s.parse(), Ok(Tile::X));
+        }
+        for s in ["O", "o", "0"] {
+            assert_eq!(s.parse(), Ok(Tile::O));
+        }
+        for s in [" ", "."] {
+            assert_eq!(s.parse(), Ok(Tile::Empty));
+        }
+        assert_eq!(
+            "Z".parse::<Tile>(),
+            Err(ParseError::InvalidTile("Z".to_string()))
+        );
+        assert!("XX".parse::<Tile>().is_err());
+    }
+
+    #[test]
+    fn test_tile_display() {
+        for (tile, plain, alternate) in [
+            (Tile::X, "X", "X"),
+            (Tile::O, "O", "O"),
+            (Tile::Empty, " ", "-"),
+        ] {
+            assert_eq!(format!("{tile}"), plain);
+            assert_eq!(format!("{tile:#}"), alternate);
+        }
+    }
+
+    #[test]
+    fn test_row_is_complete() {
+        let row = Row {
+            tiles: vec![Tile::X, Tile::X, Tile::X],
+        };
+        assert!(row.is_complete(Tile::X));
+    }
+
+    #[test]
+    fn test_diagonal_is_complete() {
+        let diag = Diagonal {
+            tiles: vec![&Tile::X, &Tile::X, &Tile::X],
+        };
+        assert!(diag.is_complete(Tile::X));
+    }
+
+    #[test]
+    fn test_game_is_complete() {
+        let mut game = Game::new();
+        game.play(0).unwrap();
+        game.play(1).unwrap();
+        game.play(3).unwrap();
+        game.play(2).unwrap();
+        game.play(6).unwrap();
+        assert!(game.is_complete());
+    }
+    #[test]
+    fn test_game_is_tie() {
+        let mut game = Game::new();
+        for index in [0, 1, 2, 3, 4, 6, 5, 8] {
+            game.play(index).unwrap();
+        }
+        // The last square can't change the result, so the draw is already called
+        assert_eq!(game.state(), GameState::Draw);
+        assert_eq!(game.play(7), Err(MoveError::GameOver));
+        assert!(!game.is_tie());
+        game.board[2].tiles[1] = Tile::X;
+        assert!(game.is_tie());
+    }
+
+    #[test]
+    fn test_forced_draw_with_empty_squares() {
+        let mut game = play_all(&[0, 1, 2, 3, 5, 8]);
+        assert!(!game.is_forced_draw());
+        assert!(!game.game_over());
+        game.play(7).unwrap();
+        assert!(game.is_forced_draw());
+        assert!(!game.is_tie());
+        assert_eq!(game.state(), GameState::Draw);
+    }
+
+    #[test]
+    fn test_win_on_last_move_is_not_tie() {
+        let mut game = Game::new();
+        for index in [0, 1, 2, 3, 4, 5, 7, 6, 8] {
+            game.play(index).unwrap();
+        }
+        assert!(!game.is_tie());
+        assert_eq!(game.state(), GameState::Win(Tile::X));
+    }
+
+    #[test]
+    fn test_4x4_row_win() {
+        let mut game = Game::with_size(4);
+        for index in [4, 0, 5, 1, 6, 2] {
+            game.play(index).unwrap();
+        }
+        assert!(!game.game_over());
+        game.play(7).unwrap();
+        assert!(game.any_row_complete(Tile::X));
+        assert_eq!(game.winner(), Some(Tile::X));
+    }
+
+    #[test]
+    fn test_4x4_anti_diagonal_win() {
+        let mut game = Game::with_size(4);
+        for index in [3, 0, 6, 1, 9, 2] {
+            game.play(index).unwrap();
+        }
+        assert!(!game.game_over());
+        game.play(12).unwrap();
+        assert!(game.any_diagonal_complete(Tile::X));
+        assert_eq!(game.winner(), Some(Tile::X));
+    }
+
+    #[test]
+    fn test_5x5_k4_run_in_middle_of_row() {
+        let mut game = Game::with_rules(5, 5, 4);
+        for index in [11, 0, 12, 1, 13, 2] {
+            game.play(index).unwrap();
+        }
+        assert!(!game.game_over());
+        game.play(14).unwrap();
+        assert!(game.any_row_complete(Tile::X));
+        assert_eq!(game.winner(), Some(Tile::X));
+    }
+
+    #[test]
+    fn test_5x5_k4_off_center_diagonal() {
+        let mut game = Game::with_rules(5, 5, 4);
+        for index in [1, 0, 7, 2, 13, 3] {
+            game.play(index).unwrap();
+        }
+        assert!(!game.game_over());
+        game.play(19).unwrap();
+        assert!(game.any_diagonal_complete(Tile::X));
+    }
+
+    #[test]
+    fn test_state_in_progress() {
+        let mut game = Game::new();
+        assert_eq!(game.state(), GameState::InProgress);
+        game.play(4).unwrap();
+        assert_eq!(game.state(), GameState::InProgress);
+        assert!(!game.game_over());
+    }
+
+    #[test]
+    fn test_state_win_for_o() {
+        let mut game = Game::new();
+        for index in [0, 3, 1, 4, 8, 5] {
+            game.play(index).unwrap();
+        }
+        assert_eq!(game.state(), GameState::Win(Tile::O));
+        assert!(game.game_over());
+    }
+
+    fn play_all(moves: &[usize]) -> Game {
+        let mut game = Game::new();
+        for &index in moves {
+            game.play(index).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn test_winning_line_row() {
+        let game = play_all(&[3, 0, 4, 1, 5]);
+        assert_eq!(game.winning_line(), Some(WinningLine::Row(1)));
+    }
+
+    #[test]
+    fn test_winning_line_col() {
+        let game = play_all(&[2, 0, 5, 1, 8]);
+        assert_eq!(game.winning_line(), Some(WinningLine::Col(2)));
+    }
+
+    #[test]
+    fn test_winning_line_diagonal() {
+        let game = play_all(&[0, 1, 4, 2, 8]);
+        assert_eq!(game.winning_line(), Some(WinningLine::Diagonal));
+    }
+
+    #[test]
+    fn test_winning_line_anti_diagonal() {
+        let game = play_all(&[2, 0, 4, 1, 6]);
+        assert_eq!(game.winning_line(), Some(WinningLine::AntiDiagonal));
+        assert_eq!(play_all(&[2, 0, 4]).winning_line(), None);
+    }
+
+    #[test]
+    fn test_undo_restores_board_and_player() {
+        let mut game = play_all(&[0, 4, 8]);
+        assert_eq!(game.undo(), Some(8));
+        assert_eq!(game.undo(), Some(4));
+        assert_eq!(game.current_player(), Tile::O);
+        assert_eq!(game.turn, 1);
+        assert_eq!(game.board[0].tiles, vec![Tile::X, Tile::Empty, Tile::Empty]);
+        assert!(game.board[1..]
+            .iter()
+            .all(|row| row.tiles.iter().all(|&t| t == Tile::Empty)));
+    }
+
+    #[test]
+    fn test_undo_clears_win_and_empty_history() {
+        let mut game = play_all(&[0, 3, 1, 4, 2]);
+        assert_eq!(game.state(), GameState::Win(Tile::X));
+        game.undo();
+        assert_eq!(game.state(), GameState::InProgress);
+        assert_eq!(game.current_player(), Tile::X);
+        assert_eq!(Game::new().undo(), None);
+    }
+
+    #[test]
+    fn test_redo_restores_identical_state() {
+        let mut game = play_all(&[0, 3, 1, 4]);
+        game.play(2).unwrap();
+        let (board, player, turn) = (game.board.clone(), game.player, game.turn);
+
+        assert_eq!(game.undo(), Some(2));
+        assert_eq!(game.redo(), Some(2));
+        assert!(game.board == board);
+        assert_eq!(game.player, player);
+        assert_eq!(game.turn, turn);
+        assert_eq!(game.state(), GameState::Win(Tile::X));
+        assert_eq!(game.redo(), None);
+    }
+
+    #[test]
+    fn test_play_clears_redo() {
+        let mut game = play_all(&[0, 4]);
+        game.undo();
+        game.play(8).unwrap();
+        assert_eq!(game.redo(), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_round_trip() {
+        let game = play_all(&[4, 0, 8]);
+        let loaded = Game::from_json(&game.to_json()).unwrap();
+        assert!(loaded.board == game.board);
+        assert_eq!(loaded.player, game.player);
+        assert_eq!(loaded.turn, game.turn);
+        assert_eq!(loaded.state, game.state);
+
+        let game = play_all(&[0, 3, 1, 4, 2]);
+        let loaded = Game::from_json(&game.to_json()).unwrap();
+        assert_eq!(loaded.state(), GameState::Win(Tile::X));
+        assert!(Game::from_json("{").is_err());
+
+        let mut game = play_all(&[4]);
+        game.resign(Tile::X).unwrap();
+        let loaded = Game::from_json(&game.to_json()).unwrap();
+        assert_eq!(loaded.outcome_message().unwrap(), "O wins, X resigned");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_rejects_inconsistent_saves() {
+        let json = play_all(&[4, 0, 8]).to_json();
+        let wider = json.replace("\"width\":3", "\"width\":5");
+        assert_ne!(wider, json);
+        assert!(Game::from_json(&wider).is_err());
+        let long_line = json.replace("\"k\":3", "\"k\":4");
+        assert!(Game::from_json(&long_line).is_err());
+        let wrong_turn = json.replace("\"turn\":3", "\"turn\":4");
+        assert!(Game::from_json(&wrong_turn).is_err());
+
+        let mut game = Game::with_rules(5, 5, 3);
+        game.set_variant(Variant::Misere);
+        let json = game.to_json();
+        let three_misere = json.replace("\"players\":2", "\"players\":3");
+        assert_ne!(three_misere, json);
+        assert!(Game::from_json(&three_misere).is_err());
+
+        let mut game = play_all(&[4, 0]);
+        game.undo();
+        let json = game.to_json();
+        assert!(Game::from_json(&json).is_ok());
+        for broken in [
+            json.replace("\"undone\":[[0,\"O\"]]", "\"undone\":[[4,\"O\"]]"),
+            json.replace("\"undone\":[[0,\"O\"]]", "\"undone\":[[0,\"Empty\"]]"),
+            json.replace("\"starter\":\"X\"", "\"starter\":\"Empty\""),
+        ] {
+            assert_ne!(broken, json);
+            assert_eq!(
+                Game::from_json(&broken).unwrap_err().to_string(),
+                "the board doesn't match its size"
+            );
+        }
+    }
+
+    #[test]
+    fn test_hint_finds_only_winning_move() {
+        assert_eq!(play_all(&[0, 3, 1, 4]).hint(), Some(2));
+        assert_eq!(play_all(&[0, 3, 1, 4, 2]).hint(), None);
+    }
+
+    #[test]
+    fn test_legal_moves() {
+        assert!(Game::new().legal_moves().eq(0..=8));
+        let game = play_all(&[4, 0, 8]);
+        assert_eq!(
+            game.legal_moves().collect::<Vec<_>>(),
+            vec![1, 2, 3, 5, 6, 7]
+        );
+        assert_eq!(play_all(&[0, 3, 1, 4, 2]).legal_moves().count(), 0);
+    }
+
+    #[test]
+    fn test_announcement_uses_names() {
+        let mut game = Game::with_names("Alice", "Bob");
+        assert_eq!(game.player_label(Tile::O), "Bob (O)");
+        assert_eq!(game.announcement(), None);
+        for index in [0, 3, 1, 4, 2] {
+            game.play(index).unwrap();
+        }
+        assert_eq!(game.announcement().unwrap(), "Alice (X) wins!");
+        assert_eq!(
+            play_all(&[0, 3, 1, 4, 2]).announcement().unwrap(),
+            "Player X wins!"
+        );
+    }
+
+    #[test]
+    fn test_outcome_message() {
+        assert_eq!(Game::new().outcome_message(), None);
+        assert_eq!(
+            play_all(&[0, 3, 1, 4, 2]).outcome_message().unwrap(),
+            "X wins on the top row"
+        );
+        assert_eq!(
+            play_all(&[0, 2, 1, 4, 3, 6]).outcome_message().unwrap(),
+            "O wins on the anti-diagonal"
+        );
+        assert_eq!(
+            Game::from_notation("XOXXOOOXX")
+                .unwrap()
+                .outcome_message()
+                .unwrap(),
+            "Draw — board full"
+        );
+        assert_eq!(
+            play_all(&[0, 1, 2, 3, 4, 6, 5, 8])
+                .outcome_message()
+                .unwrap(),
+            "Draw — no line can be completed"
+        );
+
+        let mut game = Game::new();
+        game.set_variant(Variant::Misere);
+        for index in [0, 3, 1, 4, 2] {
+            game.play(index).unwrap();
+        }
+        assert_eq!(
+            game.outcome_message().unwrap(),
+            "O wins, X completed the top row"
+        );
+
+        let mut game = play_all(&[4]);
+        game.resign(Tile::X).unwrap();
+        assert_eq!(game.outcome_message().unwrap(), "O wins, X resigned");
+        let mut game = play_all(&[4, 0]);
+        game.agree_draw().unwrap();
+        assert_eq!(game.outcome_message().unwrap(), "Draw by agreement");
+        let mut game = play_all(&[4]);
+        game.forfeit().unwrap();
+        assert_eq!(game.outcome_message().unwrap(), "X wins by forfeit");
+    }
+
+    #[test]
+    fn test_reset_matches_fresh_game() {
+        let mut game = Game::with_names("Alice", "Bob");
+        for index in [0, 3, 1, 4, 2] {
+            game.play(index).unwrap();
+        }
+        game.reset();
+        let fresh = Game::new();
+        assert!(game.board == fresh.board);
+        assert_eq!(game.player, fresh.player);
+        assert_eq!(game.turn, fresh.turn);
+        assert_eq!(game.state, fresh.state);
+        assert_eq!(game.history, fresh.history);
+        assert_eq!(game.undo(), None);
+        assert_eq!(game.player_label(Tile::X), "Alice (X)");
+    }
+
+    #[test]
+    fn test_accessors_after_a_few_moves() {
+        let mut game = Game::from_moves(&[4, 0]).unwrap();
+        assert_eq!(game.current_player(), Tile::X);
+        assert_eq!(game.turn(), 2);
+        assert_eq!(game.winner(), None);
+
+        game.play(2).unwrap();
+        assert_eq!(game.current_player(), Tile::O);
+        assert_eq!(game.turn(), 3);
+        game.undo();
+        assert_eq!(game.turn(), 2);
+
+        for index in [2, 1, 6] {
+            game.play(index).unwrap();
+        }
+        assert_eq!(game.winner(), Some(Tile::X));
+        assert_eq!(game.turn(), 5);
+    }
+
+    #[test]
+    fn test_same_board() {
+        let game = play_all(&[0, 4, 8, 2]);
+        let reordered = play_all(&[8, 2, 0, 4]);
+        assert_ne!(game, reordered);
+        assert!(game.same_board(&reordered));
+
+        let mut forfeited = reordered.clone();
+        forfeited.forfeit().unwrap();
+        assert!(game.same_board(&forfeited));
+        assert!(!game.same_board(&play_all(&[0, 4, 8])));
+        assert!(!Game::new().same_board(&Game::with_size(4)));
+    }
+
+    #[test]
+    fn test_as_array() {
+        let game = play_all(&[4, 0, 8, 2]);
+        let cells: [Tile; 9] = game.as_array().unwrap();
+        for (i, &tile) in cells.iter().enumerate() {
+            assert_eq!(tile, game.board[i / 3].tiles[i % 3]);
+        }
+        assert_eq!(game.as_array::<16>(), None);
+    }
+
+    #[test]
+    fn test_moves_played_follows_undo() {
+        let filled = |game: &Game| {
+            game.board_key()
+                .iter()
+                .filter(|&&tile| tile != Tile::Empty)
+                .count()
+        };
+        let mut game = Game::from_moves(&[4, 0, 8]).unwrap();
+        assert_eq!(game.moves_played(), 3);
+        game.undo();
+        game.undo();
+        assert_eq!(game.moves_played(), 1);
+        assert_eq!(game.moves_played(), filled(&game));
+        game.play(2).unwrap();
+        assert_eq!(game.moves_played(), 2);
+        assert_eq!(game.moves_played(), filled(&game));
+        game.redo();
+        assert_eq!(game.moves_played(), filled(&game));
+    }
+
+    #[test]
+    fn test_misere_line_loses() {
+        let mut game = Game::new();
+        game.set_variant(Variant::Misere);
+        for index in [0, 3, 1, 4, 2] {
+            game.play(index).unwrap();
+        }
+        assert_eq!(game.winner_on_board(), Some(Tile::X));
+        assert_eq!(game.state(), GameState::Win(Tile::O));
+    }
+
+    #[test]
+    fn test_wild_placement() {
+        let mut game = Game::new();
+        assert_eq!(game.place(0, Tile::O), Err(MoveError::WrongSymbol));
+        game.set_variant(Variant::Wild);
+        assert_eq!(game.place(0, Tile::Empty), Err(MoveError::WrongSymbol));
+        game.place(0, Tile::O).unwrap();
+        game.place(4, Tile::X).unwrap();
+        game.place(1, Tile::O).unwrap();
+        assert_eq!(game.current_player(), Tile::O);
+        game.place(8, Tile::X).unwrap();
+        assert_eq!(game.state(), GameState::InProgress);
+        // The X player completes a row of O's, which wins for them
+        game.place(2, Tile::O).unwrap();
+        assert_eq!(game.winner_on_board(), Some(Tile::O));
+        assert_eq!(game.winner(), Some(Tile::X));
+
+        game.undo();
+        assert_eq!(game.current_player(), Tile::X);
+        game.redo();
+        assert_eq!(game.tile_at(2), Tile::O);
+        assert_eq!(game.winner(), Some(Tile::X));
+    }
+
+    #[test]
+    fn test_move_order() {
+        let mut game = play_all(&[4, 0, 8]);
+        let order = game.move_order();
+        assert_eq!(order.len(), 9);
+        assert_eq!((order[4], order[0], order[8]), (Some(1), Some(2), Some(3)));
+        assert_eq!(order.iter().flatten().count(), 3);
+
+        game.undo();
+        assert_eq!(game.move_order()[8], None);
+        game.play(2).unwrap();
+        assert_eq!(game.move_order()[2], Some(3));
+    }
+
+    #[test]
+    fn test_squares_remaining() {
+        let mut game = Game::new();
+        assert_eq!(game.squares_remaining(), 9);
+        for (played, index) in [4, 0, 8].into_iter().enumerate() {
+            game.play(index).unwrap();
+            assert_eq!(game.squares_remaining(), 8 - played);
+        }
+        let _ = game.play(4);
+        assert_eq!(game.squares_remaining(), 6);
+    }
+
+    #[test]
+    fn test_on_end_is_called_once_with_the_result() {
+        let ended = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut game = Game::new();
+        let record = std::sync::Arc::clone(&ended);
+        game.set_on_end(move |state| record.lock().unwrap().push(*state));
+        for index in [0, 3, 1, 4] {
+            game.play(index).unwrap();
+        }
+        assert!(ended.lock().unwrap().is_empty());
+        game.clone().play(2).unwrap();
+        assert!(ended.lock().unwrap().is_empty());
+        game.play(2).unwrap();
+        assert_eq!(game.play(8), Err(MoveError::GameOver));
+        assert_eq!(*ended.lock().unwrap(), [GameState::Win(Tile::X)]);
+
+        game.reset();
+        game.agree_draw().unwrap();
+        assert_eq!(
+            *ended.lock().unwrap(),
+            [GameState::Win(Tile::X), GameState::Draw]
+        );
+    }
+
+    #[test]
+    fn test_empty_coords() {
+        let all: Vec<(usize, usize)> = (0..3)
+            .flat_map(|row| (0..3).map(move |col| (row, col)))
+            .collect();
+        assert_eq!(Game::new().empty_coords(), all);
+        assert_eq!(
+            play_all(&[4, 0, 8, 5]).empty_coords(),
+            [(0, 1), (0, 2), (1, 0), (2, 0), (2, 1)]
+        );
+        assert_eq!(
+            Game::with_rules(3, 2, 2).empty_coords().last(),
+            Some(&(1, 2))
+        );
+    }
+
+    #[test]
+    fn test_observers_see_moves_then_the_end() {
+        #[derive(PartialEq, Eq, Debug)]
+        enum Event {
+            Move(usize, Tile),
+            End(GameState),
+        }
+
+        struct Recorder(std::sync::Arc<std::sync::Mutex<Vec<Event>>>);
+
+        impl GameObserver for Recorder {
+            fn on_move(&mut self, index: usize, tile: Tile) {
+                self.0.lock().unwrap().push(Event::Move(index, tile));
+            }
+
+            fn on_end(&mut self, state: GameState) {
+                self.0.lock().unwrap().push(Event::End(state));
+            }
+        }
+
+        let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut game = Game::new();
+        game.add_observer(Recorder(std::sync::Arc::clone(&events)));
+        for index in [4, 0, 8] {
+            game.play(index).unwrap();
+        }
+        game.resign(Tile::O).unwrap();
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                Event::Move(4, Tile::X),
+                Event::Move(0, Tile::O),
+                Event::Move(8, Tile::X),
+                Event::End(GameState::Win(Tile::X)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_count_games() {
+        assert_eq!(Game::new().count_games(), 255_168);
+        assert_eq!(play_all(&[0, 3, 1, 4, 2]).count_games(), 1);
+        // Two squares left and neither side can win: both orders fill the board
+        let drawn = play_all(&[0, 1, 2, 3, 5, 8, 7]);
+        assert_eq!(drawn.state(), GameState::Draw);
+        assert_eq!(drawn.count_games(), 2);
+    }
+
+    #[test]
+    fn test_nth_legal_move_wraps() {
+        let game = Game::from_moves(&[4, 0, 8]).unwrap();
+        let empty: Vec<usize> = game.legal_moves().collect();
+        assert_eq!(empty, [1, 2, 3, 5, 6, 7]);
+        for n in 0..20 {
+            let index = game.nth_legal_move(n).unwrap();
+            assert_eq!(index, empty[n % empty.len()]);
+            assert_eq!(game.tile_at(index), Tile::Empty);
+        }
+        assert_eq!(play_all(&[0, 3, 1, 4, 2]).nth_legal_move(0), None);
+    }
+
+    #[test]
+    fn test_borrowed_columns_and_diagonals() {
+        let game = play_all(&[4, 0, 8, 2, 6]);
+        let cols = game.cols();
+        for (col, copied) in cols.iter().enumerate() {
+            assert!(game.column(col).eq(copied.tiles.iter()));
+        }
+        let [main, anti] = game.diagonals();
+        assert!(game.diagonal(false).eq(main.tiles.iter().copied()));
+        assert!(game.diagonal(true).eq(anti.tiles.iter().copied()));
+
+        let wide = Game::with_rules(4, 3, 3);
+        assert_eq!(wide.column(3).count(), 3);
+        assert_eq!(wide.diagonal(true).count(), 3);
+    }
+
+    #[test]
+    fn test_open_lines() {
+        let game = Game::new();
+        assert_eq!(game.open_lines(Tile::X), 8);
+        assert_eq!(game.open_lines(Tile::O), 8);
+
+        let game = play_all(&[4, 0]);
+        assert_eq!(game.open_lines(Tile::X), 5);
+        assert_eq!(game.open_lines(Tile::O), 4);
+    }
+
+    #[test]
+    fn test_winning_moves() {
+        let game = play_all(&[0, 4, 1]);
+        assert_eq!(game.winning_moves(Tile::X), [2]);
+        assert_eq!(game.winning_moves(Tile::O), Vec::<usize>::new());
+
+        let fork = play_all(&[0, 4, 2, 1, 6]);
+        assert_eq!(fork.winning_moves(Tile::X), [3]);
+        let fork = play_all(&[0, 1, 2, 5, 6]);
+        assert_eq!(fork.winning_moves(Tile::X), [3, 4]);
+        assert!(play_all(&[0, 3, 1, 4, 2]).winning_moves(Tile::O).is_empty());
+    }
+
+    #[test]
+    fn test_forks() {
+        // X holds opposite corners around O's centre, and 6 threatens both 3 and 7
+        let game = play_all(&[0, 4, 8, 1]);
+        assert_eq!(game.forks(Tile::X), [6]);
+        assert!(game.forks(Tile::O).contains(&3));
+        assert!(Game::new().forks(Tile::X).is_empty());
+        assert!(play_all(&[0, 3, 1, 4, 2]).forks(Tile::O).is_empty());
+    }
+
+    #[test]
+    fn test_threats() {
+        let game = Game::from_notation("XX.OO.O.X").unwrap();
+        assert_eq!(game.threats(), (vec![2], vec![2, 5]));
+        assert_eq!(Game::new().threats(), (Vec::new(), Vec::new()));
+    }
+
+    #[test]
+    fn test_is_valid() {
+        assert!(Game::new().is_valid());
+        assert!(play_all(&[4, 0, 8]).is_valid());
+        assert!(play_all(&[0, 3, 1, 4, 2]).is_valid());
+        let mut forfeited = play_all(&[4]);
+        forfeited.forfeit().unwrap();
+        assert!(forfeited.is_valid());
+
+        let mut game = play_all(&[4, 0]);
+        game.board[2].tiles[2] = Tile::O;
+        game.turn += 1;
+        assert!(!game.is_valid());
+
+        let mut game = play_all(&[4, 0, 8]);
+        game.state = GameState::Win(Tile::X);
+        assert!(!game.is_valid());
+
+        let mut wrong_turn = play_all(&[4, 0, 8]);
+        wrong_turn.player = Tile::X;
+        assert!(!wrong_turn.is_valid());
+
+        // O completed the middle row, then X went on to move anyway
+        let mut late = play_all(&[0, 3, 1, 4, 6, 5]);
+        assert_eq!(late.state(), GameState::Win(Tile::O));
+        late.board[2].tiles[2] = Tile::X;
+        late.turn += 1;
+        late.player = Tile::O;
+        assert!(!late.is_valid());
+    }
+
+    #[test]
+    fn test_forfeit() {
+        let mut game = Game::from_moves(&[4]).unwrap();
+        game.forfeit().unwrap();
+        assert_eq!(game.winner(), Some(Tile::X));
+        assert_eq!(game.play(0), Err(MoveError::GameOver));
+        assert_eq!(game.forfeit(), Err(MoveError::GameOver));
+    }
+
+    #[test]
+    fn test_resign_and_agreed_draw() {
+        let mut game = Game::from_moves(&[4]).unwrap();
+        assert_eq!(game.resign(Tile::Empty), Err(MoveError::WrongSymbol));
+        game.resign(Tile::X).unwrap();
+        assert_eq!(game.winner(), Some(Tile::O));
+        assert!(game.is_valid());
+        assert_eq!(game.resign(Tile::O), Err(MoveError::GameOver));
+
+        let mut game = Game::from_moves(&[4, 0]).unwrap();
+        game.agree_draw().unwrap();
+        assert_eq!(game.state(), GameState::Draw);
+        assert!(game.is_valid());
+        assert_eq!(game.agree_draw(), Err(MoveError::GameOver));
+
+        let mut game = Game::from_moves(&[4, 0]).unwrap();
+        game.undo();
+        game.resign(Tile::X).unwrap();
+        assert_eq!(game.redo(), None);
+        assert_eq!(game.winner(), Some(Tile::O));
+    }
+
+    #[test]
+    fn test_from_moves() {
+        let game = Game::from_moves(&[0, 3, 1, 4, 2]).unwrap();
+        assert_eq!(game.state(), GameState::Win(Tile::X));
+        assert_eq!(game.winning_line(), Some(WinningLine::Row(0)));
+        assert_eq!(Game::from_moves(&[0, 0]).err(), Some(MoveError::Occupied));
+        assert_eq!(Game::from_moves(&[9]).err(), Some(MoveError::OutOfBounds));
+        assert_eq!(
+            Game::from_moves(&[0, 3, 1, 4, 2, 5]).err(),
+            Some(MoveError::GameOver)
+        );
+    }
+
+    #[test]
+    fn test_clone_equality() {
+        let game = Game::from_moves(&[4, 0]).unwrap();
+        let mut copy = game.clone();
+        assert_eq!(copy, game);
+        copy.play(8).unwrap();
+        assert_ne!(copy, game);
+    }
+
+    #[test]
+    fn test_board_key() {
+        use std::collections::HashSet;
+        let a = Game::from_moves(&[0, 4]).unwrap();
+        let b = Game::from_moves(&[4, 0]).unwrap();
+        let mut seen = HashSet::new();
+        seen.insert(a.board_key());
+        seen.insert(b.board_key());
+        seen.insert(Game::from_moves(&[0, 4]).unwrap().board_key());
+        assert_eq!(seen.len(), 2);
+        assert_eq!(
+            a.board_key()[..5],
+            [Tile::X, Tile::Empty, Tile::Empty, Tile::Empty, Tile::O]
+        );
+    }
+
+    #[test]
+    fn test_three_player_game() {
+        let mut game = Game::with_rules(5, 5, 3);
+        game.set_players(3);
+        for index in [0, 6, 20, 2, 14, 21, 4, 16] {
+            game.play(index).unwrap();
+        }
+        assert_eq!(game.current_player(), Tile::Y);
+        assert_eq!(game.place(22, Tile::X), Err(MoveError::WrongSymbol));
+        game.play(22).unwrap();
+        assert_eq!(game.winner(), Some(Tile::Y));
+        assert_eq!(game.winning_cells(), Some(vec![20, 21, 22]));
+
+        game.undo();
+        assert_eq!(game.current_player(), Tile::Y);
+        game.undo();
+        assert_eq!(game.current_player(), Tile::O);
+        game.forfeit().unwrap();
+        assert_eq!(game.winner(), Some(Tile::X));
+
+        let mut game = Game::new();
+        assert_eq!(game.place(0, Tile::Y), Err(MoveError::WrongSymbol));
+        game.set_players(3);
+        assert_eq!(game.marks(), [Tile::X, Tile::O, Tile::Y]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Misere needs two players")]
+    fn test_three_players_reject_misere() {
+        let mut game = Game::with_rules(5, 5, 3);
+        game.set_variant(Variant::Misere);
+        game.set_players(3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Wild needs two players")]
+    fn test_wild_rejects_three_players() {
+        let mut game = Game::with_rules(5, 5, 3);
+        game.set_players(3);
+        game.set_variant(Variant::Wild);
+    }
+
+    #[test]
+    fn test_cell_kind() {
+        let game = Game::new();
+        assert_eq!(game.cell_kind(4), Some(CellKind::Center));
+        for index in [0, 2, 6, 8] {
+            assert_eq!(game.cell_kind(index), Some(CellKind::Corner));
+        }
+        for index in [1, 3, 5, 7] {
+            assert_eq!(game.cell_kind(index), Some(CellKind::Edge));
+        }
+        assert_eq!(game.cell_kind(9), None);
+        assert_eq!(Game::with_size(4).cell_kind(10), Some(CellKind::Center));
+    }
+
+    #[test]
+    fn test_win_lines() {
+        assert_eq!(WIN_LINES.len(), 8);
+        for line in WIN_LINES {
+            assert!(line.iter().all(|&index| index < 9));
+            assert!(line[0] != line[1] && line[1] != line[2] && line[0] != line[2]);
+        }
+        let distinct: std::collections::HashSet<_> = WIN_LINES.iter().collect();
+        assert_eq!(distinct.len(), 8);
+        // Same lines the general m,n,k scan finds
+        let scanned: std::collections::HashSet<Vec<usize>> =
+            Game::new().scan_runs().into_iter().collect();
+        let expected = WIN_LINES.iter().map(|line| line.to_vec()).collect();
+        assert_eq!(scanned, expected);
+    }
+
+    #[test]
+    fn test_lines() {
+        let game = Game::from_moves(&[0, 4]).unwrap();
+        let lines: Vec<Vec<Tile>> = game.lines().collect();
+        assert_eq!(lines.len(), 8);
+        assert!(lines.iter().all(|line| line.len() == 3));
+        assert!(lines.contains(&vec![Tile::X, Tile::O, Tile::Empty]));
+        assert_eq!(Game::with_size(4).lines().count(), 10);
+        assert_eq!(Game::with_rules(4, 4, 3).lines().count(), 24);
+    }
+
+    #[test]
+    fn test_no_moves_after_game_over() {
+        let mut game = Game::from_moves(&[0, 3, 1, 4, 2]).unwrap();
+        let grid = game.grid();
+        assert_eq!(game.play(5), Err(MoveError::GameOver));
+        assert_eq!(game.grid(), grid);
+        assert_eq!(game.winner(), Some(Tile::X));
+        assert_eq!(game.turn, 5);
+    }
+
+    #[test]
+    fn test_grid_reflects_board() {
+        let mut game = Game::new();
+        game.play(4).unwrap();
+        let grid = game.grid();
+        assert_eq!(grid.len(), 3);
+        assert_eq!(grid[1][1], Tile::X);
+        assert_eq!(grid[0], vec![Tile::Empty; 3]);
+        assert_eq!(
+            Game::with_rules(4, 2, 2).grid(),
+            vec![vec![Tile::Empty; 4]; 2]
+        );
+    }
+
+    #[test]
+    fn test_canonical_matches_mirrored_boards() {
+        let left = play_all(&[0, 4, 3]);
+        let right = play_all(&[2, 4, 5]);
+        let rotated = play_all(&[8, 4, 7]);
+        assert_ne!(left.grid(), right.grid());
+        assert_eq!(left.canonical(), right.canonical());
+        assert_eq!(left.canonical(), rotated.canonical());
+        assert_ne!(left.canonical(), play_all(&[0, 4, 8]).canonical());
+
+        let openings: std::collections::BTreeSet<_> =
+            (0..9).map(|index| play_all(&[index]).canonical()).collect();
+        assert_eq!(openings.len(), 3);
+    }
+
+    #[test]
+    fn test_play_out_of_bounds() {
+        let mut game = Game::new();
+        assert_eq!(game.play(9), Err(MoveError::OutOfBounds));
+        assert_eq!(game.play(usize::MAX), Err(MoveError::OutOfBounds));
+        assert_eq!(game.turn, 0);
+        assert_eq!(game.current_player(), Tile::X);
+    }
+
+    #[test]
+    fn test_play_at() {
+        let mut by_coords = Game::new();
+        let mut by_index = Game::new();
+        by_coords.play_at(1, 1).unwrap();
+        by_index.play(4).unwrap();
+        assert_eq!(by_coords, by_index);
+        assert_eq!(by_coords.play_at(3, 0), Err(MoveError::OutOfBounds));
+        assert_eq!(by_coords.play_at(0, 3), Err(MoveError::OutOfBounds));
+        assert_eq!(by_coords.play_at(1, 1), Err(MoveError::Occupied));
+    }
+
+    #[test]
+    fn test_play_occupied_keeps_turn() {
+        let mut game = Game::new();
+        game.play(4).unwrap();
+        assert_eq!(game.play(4), Err(MoveError::Occupied));
+        assert_eq!(game.turn, 1);
+        assert_eq!(game.player, Tile::O);
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn test_turn_matches_board(
+            size in 3usize..6,
+            picks in proptest::collection::vec(proptest::prelude::any::<usize>(), 0..40),
+        ) {
+            let mut game = Game::with_size(size);
+            for pick in picks {
+                let legal: Vec<usize> = game.legal_moves().collect();
+                if legal.is_empty() {
+                    break;
+                }
+                game.play(legal[pick % legal.len()]).unwrap();
+                // A rejected move must not count
+                let last = *game.history.last().unwrap();
+                proptest::prop_assert!(game.play(last).is_err());
+
+                let filled = game
+                    .board_key()
+                    .iter()
+                    .filter(|&&tile| tile != Tile::Empty)
+                    .count();
+                proptest::prop_assert_eq!(usize::from(game.turn), filled);
+                proptest::prop_assert_eq!(game.moves_played(), filled);
+                let completed: std::collections::HashSet<Tile> = game
+                    .lines()
+                    .filter(|line| line[0] != Tile::Empty && line.is_complete(line[0]))
+                    .map(|line| line[0])
+                    .collect();
+                proptest::prop_assert!(completed.len() <= 1);
+                proptest::prop_assert!(game.is_valid());
+            }
+        }
+    }
+}
@@ -1,274 +1,880 @@
 // Tic-Tac-Toe game in Rust
+// The CLI needs a terminal, stdin and threads, which a wasm32 build doesn't have. There
+// the library is driven from JavaScript instead and this binary is an empty stub
+#![cfg_attr(target_arch = "wasm32", allow(dead_code, unused_imports))]
 
-use colored::Colorize;
-use core::fmt::{Display, Formatter};
+use quick_start::input::{self, Command};
+use quick_start::{ai, Game, GameState, Scoreboard, Theme, Variant};
 
-#[derive(PartialEq, Clone, Copy, Debug)]
-enum Tile {
-    X,
-    O,
-    Empty,
-}
+use clock::{Clock, TimeoutAction};
+use config::{Config, USAGE};
+
+mod clock;
+mod config;
+mod stats;
+#[cfg(feature = "tui")]
+mod tui;
+
+use std::io::{BufRead, Error, IsTerminal};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
 
-impl Display for Tile {
-    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
-        let symbol = match self {
-            Self::X => 'X',
-            Self::O => 'O',
-            Self::Empty => ' ',
+#[cfg(target_arch = "wasm32")]
+fn main() {}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() -> Result<(), Error> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("replay") {
+        if args.iter().any(|arg| arg == "--no-color") || !std::io::stdout().is_terminal() {
+            colored::control::set_override(false);
+        }
+        let moves: Result<Vec<usize>, _> = args[1..]
+            .iter()
+            .filter(|arg| !arg.starts_with("--"))
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|part| !part.is_empty())
+            .map(|part| input::parse_move(part, 3))
+            .collect();
+        let result = moves
+            .map_err(|err| err.to_string())
+            .and_then(|moves| replay(&moves).map_err(|err| err.to_string()));
+        if let Err(err) = result {
+            eprintln!("Can't replay that game: {err}");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    if args.first().map(String::as_str) == Some("stats") {
+        let first = args.get(1).and_then(|name| config::parse_difficulty(name));
+        let second = args.get(2).and_then(|name| config::parse_difficulty(name));
+        let games = args.get(3).map_or(Some(1000), |games| games.parse().ok());
+        let (Some(first), Some(second), Some(games)) = (first, second, games) else {
+            eprintln!("{USAGE}");
+            std::process::exit(2);
         };
-        write!(f, "{symbol}")
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        let stats = stats::first_move_stats(first, second, games, seed);
+        print!("{}", stats.table(&args[1], &args[2]));
+        return Ok(());
+    }
+    if args.first().map(String::as_str) == Some("analyze") {
+        match Game::from_notation(&args[1..].join(" ")) {
+            Ok(game) if !searchable(&game) => {
+                eprintln!("Can't analyze that position: only 3x3 boards can be searched");
+                std::process::exit(1);
+            }
+            Ok(game) => print!("{}", analysis(&game)),
+            Err(err) => {
+                eprintln!("Can't analyze that position: {err}");
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
     }
+    let config = match Config::from_args(&args) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{err}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+    if !config.color || !std::io::stdout().is_terminal() {
+        colored::control::set_override(false);
+    }
+    let seed = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let seed = config.seed.unwrap_or(seed);
+    let mut opponent = config.ai.map(|tile| Opponent {
+        ai: ai::Ai::seeded(seed),
+        tile,
+        difficulty: config.difficulty,
+    });
+    let mut clock = config.turn_time.map(|limit| Clock {
+        limit,
+        on_timeout: config.on_timeout,
+        rng: ai::Rng::seeded(seed.wrapping_add(1)),
+    });
+    let lines = spawn_stdin_reader();
+    let mut scoreboard = Scoreboard::default();
+    let mut game = Game::with_size(config.size);
+    if let Some((x_name, o_name)) = &config.names {
+        game.set_names(x_name, o_name);
+    }
+    game.set_variant(config.variant);
+    game.set_players(config.players);
+    game.set_starter(config.first);
+    let theme = if config.color_blind {
+        Theme::color_blind()
+    } else {
+        Theme::default()
+    };
+    game.set_theme(Theme {
+        labels: config.labels,
+        ..theme
+    });
+    let (bell, is_terminal) = (config.bell, std::io::stdout().is_terminal());
+    game.set_on_end(move |&state| {
+        if should_ring(bell, is_terminal, state) {
+            print!("\x07");
+        }
+    });
+    let target = config.target;
+    if let Some(path) = &config.moves_file {
+        let loaded = std::fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|contents| {
+                play_moves(&mut game, &contents, config.keypad).map_err(|err| err.to_string())
+            });
+        if let Err(err) = loaded {
+            eprintln!("Can't play the moves in {}: {err}", path.display());
+            std::process::exit(1);
+        }
+    }
+    auto_open(&mut game, config.auto_open);
+
+    #[cfg(feature = "net")]
+    if let Some(role) = &config.net {
+        return play_online(&mut game, role, config.keypad, &lines);
+    }
+
+    #[cfg(feature = "tui")]
+    if config.tui {
+        return tui::run(&mut game, |game| {
+            let opponent = opponent
+                .as_mut()
+                .filter(|opponent| opponent.tile == game.current_player())?;
+            Some(
+                opponent
+                    .ai
+                    .pick_move(game, opponent.tile, opponent.difficulty),
+            )
+        });
+    }
+
+    loop {
+        let round = play_round(
+            &mut game,
+            opponent.as_mut(),
+            clock.as_mut(),
+            RoundOptions {
+                tutorial: config.tutorial,
+                clear: should_clear(config.clear, std::io::stdout().is_terminal()),
+                keypad: config.keypad,
+            },
+            &lines,
+        )?;
+        match round {
+            Round::Finished => {}
+            Round::Abandoned => {
+                println!("Game abandoned");
+                break;
+            }
+            Round::InputClosed => {
+                println!("Input closed, leaving the game");
+                break;
+            }
+        }
+        println!("{game}");
+        if let Some(announcement) = game.announcement() {
+            println!("{announcement}");
+        }
+
+        if let Some(target) = target {
+            scoreboard.record(game.state());
+            println!("Score: {scoreboard}");
+            if let Some(leader) = scoreboard.leader(target) {
+                println!("{} takes the match!", game.player_label(leader));
+                break;
+            }
+        }
+        if !play_again(&lines)? {
+            break;
+        }
+        game.reset();
+        auto_open(&mut game, config.auto_open);
+    }
+    Ok(())
 }
 
-#[derive(PartialEq)]
-struct Row {
-    tiles: [Tile; 3],
+// Plays the next `moves` moves as the AI would for whoever is on move, stopping
+// early if that ends the game
+fn auto_open(game: &mut Game, moves: usize) {
+    for _ in 0..moves {
+        let Some(index) = game.hint() else { break };
+        game.play(index).expect("the best move is a legal move");
+    }
 }
 
-impl Row {
-    const fn new() -> Self {
-        Self {
-            tiles: [Tile::Empty; 3],
+// A game against someone on the other end of a TCP connection. The host is X and
+// tells the client the board size; every move either side sends is checked locally
+#[cfg(feature = "net")]
+fn play_online(
+    game: &mut Game,
+    role: &config::NetRole,
+    keypad: bool,
+    lines: &Receiver<Result<String, Error>>,
+) -> Result<(), Error> {
+    use quick_start::net::{Connection, Message};
+    use quick_start::Tile;
+    use std::io::ErrorKind;
+
+    let (mut connection, me) = match role {
+        config::NetRole::Host(addr) => {
+            println!("Waiting for a player to connect to {addr}");
+            let mut connection = Connection::host(addr)?;
+            connection.send(Message::Hello { size: game.width() })?;
+            (connection, Tile::X)
+        }
+        config::NetRole::Connect(addr) => {
+            let mut connection = Connection::connect(addr)?;
+            match connection.receive()? {
+                Message::Hello { size } if size == game.width() => {}
+                Message::Hello { size } => {
+                    let reason =
+                        format!("the host plays on a {size}x{size} board, use --size={size}");
+                    return Err(Error::new(ErrorKind::InvalidData, reason));
+                }
+                _ => return Err(Error::new(ErrorKind::InvalidData, "expected a greeting")),
+            }
+            (connection, Tile::O)
+        }
+    };
+    println!("Connected, you play {me}");
+
+    while !game.game_over() {
+        println!("{game}");
+        let mover = game.current_player();
+        let label = game.player_label(mover);
+        if mover != me {
+            println!("Waiting for {label}...");
+            match connection.receive()? {
+                Message::Move(index) => {
+                    println!("{label} plays {index}");
+                    game.play(index)
+                        .map_err(|err| Error::new(ErrorKind::InvalidData, err.to_string()))?;
+                }
+                Message::Resign => {
+                    println!("{label} resigns");
+                    game.resign(mover).expect("the game is still in progress");
+                }
+                Message::Hello { .. } => {
+                    return Err(Error::new(ErrorKind::InvalidData, "unexpected greeting"));
+                }
+            }
+            continue;
         }
+
+        println!("{label}, enter your move or resign:");
+        let line = match read_input(lines, None)? {
+            Input::Line(line) => line,
+            Input::Closed | Input::TimedOut => "resign".to_string(),
+        };
+        let command = if keypad {
+            input::parse_keypad_command(&line)
+        } else {
+            input::parse_command(&line, game.width())
+        };
+        match command {
+            Ok(Command::Move(index)) => match game.play(index) {
+                Ok(()) => connection.send(Message::Move(index))?,
+                Err(err) => println!("{err}"),
+            },
+            Ok(Command::Resign | Command::Quit) => {
+                connection.send(Message::Resign)?;
+                game.resign(me).expect("the game is still in progress");
+            }
+            Ok(_) => println!("Only moves and resign work in a network game"),
+            Err(err) => println!("{err}"),
+        }
+    }
+    println!("{game}");
+    if let Some(announcement) = game.announcement() {
+        println!("{announcement}");
     }
+    Ok(())
 }
 
-struct Diagonal<'a> {
-    tiles: [&'a Tile; 3],
+#[derive(PartialEq, Eq, Debug)]
+struct MovesError {
+    line: usize,
+    reason: String,
 }
 
-trait Completable {
-    fn is_complete(&self, tile: Tile) -> bool;
+impl std::fmt::Display for MovesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
 }
 
-impl Completable for [Tile; 3] {
-    fn is_complete(&self, tile: Tile) -> bool {
-        self.iter().all(|t| *t == tile)
+// Applies one command per line, as typed at the prompt, stopping at the first bad one.
+// A draw offer is accepted by an accept on the next line
+fn play_moves(game: &mut Game, contents: &str, keypad: bool) -> Result<(), MovesError> {
+    let mut offered = false;
+    for (number, line) in contents.lines().enumerate() {
+        let fail = |reason: String| MovesError {
+            line: number + 1,
+            reason,
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let command = if keypad {
+            input::parse_keypad_command(line)
+        } else {
+            input::parse_command(line, game.width())
+        }
+        .map_err(|err| fail(err.to_string()))?;
+        let was_offered = std::mem::take(&mut offered);
+        let played = match command {
+            Command::Move(index) => game.play(index),
+            Command::Resign => game.resign(game.current_player()),
+            Command::OfferDraw => {
+                offered = true;
+                Ok(())
+            }
+            Command::AcceptDraw if was_offered => game.agree_draw(),
+            Command::AcceptDraw => return Err(fail("there's no draw offer to accept".to_string())),
+            Command::Place(index, symbol) => game.place(index, symbol),
+            Command::Undo => {
+                game.undo();
+                Ok(())
+            }
+            Command::Redo => {
+                game.redo();
+                Ok(())
+            }
+            Command::Hint => Ok(()),
+            Command::Quit => break,
+        };
+        played.map_err(|err| fail(err.to_string()))?;
     }
+    Ok(())
 }
 
-impl Completable for Row {
-    fn is_complete(&self, tile: Tile) -> bool {
-        self.tiles.is_complete(tile)
+fn replay(moves: &[usize]) -> Result<(), quick_start::MoveError> {
+    Game::from_moves(moves)?;
+    let mut game = Game::new();
+    println!("{game}");
+    for &index in moves {
+        std::thread::sleep(Duration::from_millis(500));
+        println!("{} plays {index}", game.player_label(game.current_player()));
+        game.play(index)?;
+        println!("{game}");
+    }
+    if let Some(announcement) = game.announcement() {
+        println!("{announcement}");
     }
+    Ok(())
 }
 
-impl Completable for Diagonal<'_> {
-    fn is_complete(&self, tile: Tile) -> bool {
-        self.tiles.iter().all(|&t| *t == tile)
+// The best move for the player to move, how the game ends with perfect play from
+// here, and the squares that win on the spot for each side
+fn analysis(game: &Game) -> String {
+    let squares = |squares: Vec<usize>| {
+        if squares.is_empty() {
+            "none".to_string()
+        } else {
+            squares
+                .iter()
+                .map(usize::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    };
+    let best_move = game.hint().map_or_else(
+        || "none, the game is over".to_string(),
+        |index| index.to_string(),
+    );
+    let result = match game.forced_result() {
+        GameState::Win(tile) => format!("{tile} wins"),
+        GameState::Draw => "Draw".to_string(),
+        GameState::InProgress => "Undecided".to_string(),
+    };
+    let (x_threats, o_threats) = game.threats();
+    format!(
+        "{game}\nBest move for {}: {best_move}\nWith perfect play: {result}\nX threats: {}\nO threats: {}\n",
+        game.current_player(),
+        squares(x_threats),
+        squares(o_threats)
+    )
+}
+
+// The computer player, kept across rounds so its search cache carries over
+struct Opponent {
+    ai: ai::Ai,
+    tile: quick_start::Tile,
+    difficulty: ai::Difficulty,
+}
+
+impl Opponent {
+    // Takes a draw unless it can still force a win
+    fn accepts_draw(&mut self, game: &Game) -> bool {
+        self.ai
+            .ranked_moves(game, game.current_player())
+            .first()
+            .is_none_or(|&(_, score)| score >= 0)
     }
 }
 
-struct Game {
-    board: [Row; 3],
-    player: Tile,
-    winner: Tile,
-    turn: u8,
-    over: bool,
+// Stdin is read on its own thread so a turn can stop waiting when its time runs out
+fn spawn_stdin_reader() -> Receiver<Result<String, Error>> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            let failed = line.is_err();
+            if sender.send(line).is_err() || failed {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+#[derive(PartialEq, Eq, Debug)]
+enum Input {
+    Line(String),
+    TimedOut,
+    // Stdin reached EOF, so no more moves are coming
+    Closed,
+}
+
+fn read_input(
+    lines: &Receiver<Result<String, Error>>,
+    limit: Option<Duration>,
+) -> Result<Input, Error> {
+    let line = match limit {
+        Some(limit) => lines.recv_timeout(limit),
+        None => lines.recv().map_err(RecvTimeoutError::from),
+    };
+    match line {
+        Ok(line) => line.map(Input::Line),
+        Err(RecvTimeoutError::Timeout) => Ok(Input::TimedOut),
+        Err(RecvTimeoutError::Disconnected) => Ok(Input::Closed),
+    }
 }
 
-impl Game {
-    pub const fn new() -> Self {
-        Self {
-            board: [Row::new(), Row::new(), Row::new()],
-            player: Tile::X,
-            winner: Tile::Empty,
-            turn: 0,
-            over: false,
+// Asks until it gets a yes or a no. Closed input counts as a no
+fn play_again(lines: &Receiver<Result<String, Error>>) -> Result<bool, Error> {
+    loop {
+        println!("Play again? (y/n)");
+        match read_input(lines, None)? {
+            Input::Line(line) => match input::parse_yes_no(&line) {
+                Some(again) => return Ok(again),
+                None => println!("Please answer y or n"),
+            },
+            Input::Closed => return Ok(false),
+            Input::TimedOut => {}
         }
     }
+}
 
-    pub fn play(&mut self, index: usize) {
-        let row = index / 3;
-        let col = index % 3;
+#[derive(PartialEq, Eq, Debug)]
+enum Round {
+    Finished,
+    Abandoned,
+    InputClosed,
+}
 
-        if self.board[row].tiles[col] == Tile::Empty {
-            self.board[row].tiles[col] = self.player;
-        }
-        self.turn += 1;
+#[derive(Clone, Copy, Default)]
+struct RoundOptions {
+    tutorial: bool,
+    clear: bool,
+    keypad: bool,
+}
 
-        if self.is_complete() {
-            self.winner = self.player;
-            self.over = true;
-        } else if self.is_tie() {
-            self.over = true;
+// Clearing only makes sense on a terminal, and would litter piped output with escapes
+const fn should_clear(requested: bool, is_terminal: bool) -> bool {
+    requested && is_terminal
+}
+
+// A full game-tree search is only quick enough on the classic board, and it only
+// tries each side's own mark, which isn't the whole story under Wild
+const fn searchable(game: &Game) -> bool {
+    game.cell_count() <= 9 && !matches!(game.variant(), Variant::Wild)
+}
+
+// A bell for a win only, and never into a pipe
+const fn should_ring(enabled: bool, is_terminal: bool, state: GameState) -> bool {
+    enabled && is_terminal && matches!(state, GameState::Win(_))
+}
+
+fn play_round(
+    game: &mut Game,
+    mut opponent: Option<&mut Opponent>,
+    mut clock: Option<&mut Clock>,
+    options: RoundOptions,
+    lines: &Receiver<Result<String, Error>>,
+) -> Result<Round, Error> {
+    let ai_tile = opponent.as_ref().map(|opponent| opponent.tile);
+    while !game.game_over() {
+        if let Some(opponent) = opponent
+            .as_mut()
+            .filter(|opponent| game.current_player() == opponent.tile)
+        {
+            let tile = opponent.tile;
+            let index = opponent.ai.pick_move(game, tile, opponent.difficulty);
+            game.play(index).expect("AI picked an illegal move");
+            println!("{} plays {index}", game.player_label(tile));
+            continue;
+        }
+        if options.clear {
+            // Clear the screen and move the cursor home
+            print!("\x1b[2J\x1b[H");
+        }
+        println!("{game}");
+        if let Some(message) = coaching(game).filter(|_| options.tutorial) {
+            println!("{message}");
+        }
+        let squares = if options.keypad {
+            "keypad 1-9".to_string()
         } else {
-            self.player = match self.player {
-                Tile::X => Tile::O,
-                Tile::O => Tile::X,
-                Tile::Empty => panic!("Invalid player"),
-            };
+            format!("0-{}", game.cell_count() - 1)
+        };
+        let label = game.player_label(game.current_player());
+        println!(
+            "Move {}/{} — {} squares left",
+            game.moves_played() + 1,
+            game.cell_count(),
+            game.squares_remaining()
+        );
+        let wild = game.variant() == Variant::Wild;
+        let symbol = if wild { " and X or O" } else { "" };
+        match clock.as_ref() {
+            Some(clock) => println!(
+                "{label}, enter your move ({squares} or row,col){symbol} within {}s:",
+                clock.limit.as_secs()
+            ),
+            None => println!("{label}, enter your move ({squares} or row,col){symbol}:"),
+        }
+        let input = match read_input(lines, clock.as_ref().map(|clock| clock.limit))? {
+            Input::Line(input) => input,
+            Input::Closed => return Ok(Round::InputClosed),
+            Input::TimedOut => {
+                let clock = clock.as_mut().expect("only a timed turn can run out");
+                match clock.timeout_action(game) {
+                    TimeoutAction::Forfeit => {
+                        println!("Time's up! {label} forfeits");
+                        game.forfeit().expect("the game is still in progress");
+                    }
+                    TimeoutAction::Play(index) => {
+                        println!("Time's up! Playing square {index} for {label}");
+                        game.play(index).expect("timeout picked an illegal move");
+                    }
+                }
+                continue;
+            }
+        };
+        let parsed = if options.keypad {
+            input::parse_keypad_command(&input)
+        } else {
+            input::parse_command(&input, game.width())
+        };
+        let command = match parsed {
+            Ok(command) => command,
+            Err(err) if wild => {
+                println!("{err}, please enter a square and a symbol, like 4 O");
+                continue;
+            }
+            Err(err) => {
+                println!("{err}, please enter a square number or row,col");
+                continue;
+            }
+        };
+        match command {
+            Command::Undo => {
+                match game.undo() {
+                    Some(index) => println!("Took back the move on square {index}"),
+                    None => println!("Nothing to undo"),
+                }
+                if ai_tile == Some(game.current_player()) {
+                    game.undo();
+                }
+            }
+            Command::Redo => {
+                match game.redo() {
+                    Some(index) => println!("Replayed the move on square {index}"),
+                    None => println!("Nothing to redo"),
+                }
+                if ai_tile == Some(game.current_player()) {
+                    game.redo();
+                }
+            }
+            Command::Hint if game.variant() == Variant::Wild => {
+                println!("Hints aren't available in wild games");
+            }
+            Command::Hint if !searchable(game) => {
+                println!("Hints are only available on the 3x3 board");
+            }
+            Command::Hint => {
+                if let Some(index) = game.hint() {
+                    println!("Try square {index}");
+                }
+            }
+            Command::Resign => match game.resign(game.current_player()) {
+                Ok(()) => println!("{label} resigns"),
+                Err(err) => println!("{err}"),
+            },
+            Command::OfferDraw if game.players() != 2 => {
+                println!("Only two players can agree a draw");
+            }
+            Command::OfferDraw => {
+                let other = game.player_label(game.current_player().opponent());
+                let accepted = match opponent.as_mut() {
+                    Some(opponent) => opponent.accepts_draw(game),
+                    None => {
+                        println!("{label} offers a draw. {other}, type accept to agree:");
+                        match read_input(lines, None)? {
+                            Input::Line(line) => {
+                                input::parse_command(&line, game.width()) == Ok(Command::AcceptDraw)
+                            }
+                            Input::Closed => return Ok(Round::InputClosed),
+                            Input::TimedOut => false,
+                        }
+                    }
+                };
+                if accepted {
+                    println!("{other} accepts the draw");
+                    game.agree_draw().expect("the game is still in progress");
+                } else {
+                    println!("{other} declines, play on");
+                }
+            }
+            Command::AcceptDraw => println!("There's no draw offer to accept"),
+            Command::Quit => return Ok(Round::Abandoned),
+            Command::Move(index) => {
+                let blunder = options.tutorial
+                    && searchable(game)
+                    && game.is_blunder(index, game.current_player());
+                match game.play(index) {
+                    Err(err) => println!("{err}, try again"),
+                    Ok(()) if blunder => println!("Careful, a better square was available there"),
+                    Ok(()) => {}
+                }
+            }
+            Command::Place(index, symbol) => {
+                if let Err(err) = game.place(index, symbol) {
+                    println!("{err}, try again");
+                }
+            }
         }
     }
+    Ok(Round::Finished)
+}
 
-    pub fn is_complete(&self) -> bool {
-        self.any_row_complete(Tile::X)
-            || self.any_row_complete(Tile::O)
-            || self.any_diagonal_complete(Tile::X)
-            || self.any_diagonal_complete(Tile::O)
-            || self.any_col_complete(Tile::X)
-            || self.any_col_complete(Tile::O)
+// Warns the player on move about squares their opponent could win on next turn, or
+// failing that, squares where the opponent could set up two threats at once
+fn coaching(game: &Game) -> Option<String> {
+    if game.game_over() || game.variant() != Variant::Normal || game.players() != 2 {
+        return None;
     }
-
-    pub fn any_row_complete(&self, tile: Tile) -> bool {
-        self.board.iter().any(|row| row.is_complete(tile))
+    let opponent = game.current_player().opponent();
+    let player = game.player_label(opponent);
+    let join = |squares: &[usize], word: &str| {
+        let squares: Vec<String> = squares.iter().map(ToString::to_string).collect();
+        squares.join(word)
+    };
+    match game.winning_moves(opponent).as_slice() {
+        [] => match game.forks(opponent).as_slice() {
+            [] => None,
+            [square] => Some(format!("{player} could fork you on square {square}")),
+            squares => Some(format!(
+                "{player} could fork you on squares {}",
+                join(squares, " or ")
+            )),
+        },
+        [square] => Some(format!(
+            "{player} threatens to win on square {square}, block it there"
+        )),
+        squares => Some(format!(
+            "{player} threatens to win on squares {}, too many to block",
+            join(squares, " and ")
+        )),
     }
+}
 
-    pub fn any_col_complete(&self, tile: Tile) -> bool {
-        let cols = self.cols();
-        cols.iter().any(|col| col.is_complete(tile))
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quick_start::Tile;
 
-    pub fn any_diagonal_complete(&self, tile: Tile) -> bool {
-        let diags = self.diagonals();
-        diags.iter().any(|diag| diag.is_complete(tile))
+    #[test]
+    fn test_play_again_asks_until_answered() {
+        let (sender, receiver) = mpsc::channel();
+        for line in ["maybe", "Yes", "n"] {
+            sender.send(Ok(line.to_string())).unwrap();
+        }
+        drop(sender);
+        assert!(play_again(&receiver).unwrap());
+        assert!(!play_again(&receiver).unwrap());
+        assert!(!play_again(&receiver).unwrap());
     }
 
-    pub const fn diagonals(&self) -> [Diagonal; 2] {
-        [
-            Diagonal {
-                tiles: [
-                    &self.board[0].tiles[0],
-                    &self.board[1].tiles[1],
-                    &self.board[2].tiles[2],
-                ],
-            },
-            Diagonal {
-                tiles: [
-                    &self.board[0].tiles[2],
-                    &self.board[1].tiles[1],
-                    &self.board[2].tiles[0],
-                ],
-            },
-        ]
+    #[test]
+    fn test_read_input_treats_eof_as_closed() {
+        let (sender, receiver) = mpsc::channel();
+        sender.send(Ok("4".to_string())).unwrap();
+        drop(sender);
+        assert_eq!(
+            read_input(&receiver, None).unwrap(),
+            Input::Line("4".to_string())
+        );
+        assert_eq!(read_input(&receiver, None).unwrap(), Input::Closed);
+        assert_eq!(
+            read_input(&receiver, Some(Duration::from_millis(10))).unwrap(),
+            Input::Closed
+        );
     }
 
-    pub const fn cols(&self) -> [Row; 3] {
-        [
-            Row {
-                tiles: [
-                    self.board[0].tiles[0],
-                    self.board[1].tiles[0],
-                    self.board[2].tiles[0],
-                ],
-            },
-            Row {
-                tiles: [
-                    self.board[0].tiles[1],
-                    self.board[1].tiles[1],
-                    self.board[2].tiles[1],
-                ],
-            },
-            Row {
-                tiles: [
-                    self.board[0].tiles[2],
-                    self.board[1].tiles[2],
-                    self.board[2].tiles[2],
-                ],
-            },
-        ]
+    #[test]
+    fn test_read_input_times_out() {
+        let (_sender, receiver) = mpsc::channel();
+        assert_eq!(
+            read_input(&receiver, Some(Duration::from_millis(10))).unwrap(),
+            Input::TimedOut
+        );
     }
 
-    pub const fn is_tie(&self) -> bool {
-        self.turn == 9
+    #[test]
+    fn test_coaching_names_the_block() {
+        let game = Game::from_moves(&[0, 4]).unwrap();
+        assert_eq!(coaching(&game), None);
+        let game = Game::from_moves(&[0, 4, 1]).unwrap();
+        assert_eq!(
+            coaching(&game).unwrap(),
+            "Player X threatens to win on square 2, block it there"
+        );
+        let game = Game::from_moves(&[0, 4, 8]).unwrap();
+        assert_eq!(
+            coaching(&game).unwrap(),
+            "Player X could fork you on squares 2 or 6"
+        );
     }
 
-    pub const fn game_over(&self) -> bool {
-        self.over
+    #[test]
+    fn test_auto_open() {
+        let mut game = Game::new();
+        auto_open(&mut game, 2);
+        let key = game.board_key();
+        assert_eq!(key.iter().filter(|&&tile| tile == Tile::X).count(), 1);
+        assert_eq!(key.iter().filter(|&&tile| tile == Tile::O).count(), 1);
+        assert_eq!(game.current_player(), Tile::X);
+
+        // Perfect play is a draw, called before the board fills
+        let mut game = Game::new();
+        auto_open(&mut game, 9);
+        assert_eq!(game.state(), GameState::Draw);
+        assert!(game.moves_played() < 9);
     }
-}
 
-impl Display for Game {
-    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
-        for (i, row) in (0u32..).zip(self.board.iter()) {
-            for (j, tile) in (0u32..).zip(row.tiles.iter()) {
-                let symbol = match tile {
-                    Tile::X => 'X',
-                    Tile::O => 'O',
-                    Tile::Empty => char::from_digit(i * 3 + j, 10).unwrap_or(' '),
-                };
-                match symbol {
-                    'X' => write!(f, "{} ", symbol.to_string().green()),
-                    'O' => write!(f, "{} ", symbol.to_string().red()),
-                    _ => write!(f, "{symbol} "),
-                }?;
-            }
-            writeln!(f)?;
-            writeln!(f, "-----")?;
-        }
-        Ok(())
+    #[test]
+    fn test_searchable() {
+        assert!(searchable(&Game::new()));
+        assert!(!searchable(&Game::with_size(4)));
+        let mut wild = Game::new();
+        wild.set_variant(Variant::Wild);
+        assert!(!searchable(&wild));
     }
-}
 
-use std::io::Error;
+    #[test]
+    fn test_play_moves_from_a_file() {
+        let mut game = Game::new();
+        play_moves(&mut game, "4\n\n0\n2,2\n", false).unwrap();
+        assert_eq!(
+            game.board_key(),
+            Game::from_moves(&[4, 0, 8]).unwrap().board_key()
+        );
 
-fn main() -> Result<(), Error> {
-    let mut game = Game::new();
+        let mut crlf = Game::new();
+        play_moves(&mut crlf, "4\r\n\r\n0\r\n2,2\r\n", false).unwrap();
+        assert!(crlf.same_board(&game));
 
-    while !game.game_over() {
-        println!("{game}");
-        println!("Player {}, enter your move (0-8):", game.player);
-        let mut input = String::new();
-        std::io::stdin().read_line(&mut input)?;
-        if let Ok(index) = input.trim().parse() {
-            game.play(index);
-        } else {
-            println!("Invalid input, please enter a number between 0 and 8");
-            continue;
-        }
+        let mut game = Game::new();
+        let err = play_moves(&mut game, "4\n0\n4\n1\n", false).unwrap_err();
+        assert_eq!(err.line, 3);
+        assert_eq!(err.to_string(), "line 3: That square is already taken");
+        assert_eq!(game.moves_played(), 2);
+
+        let mut game = Game::new();
+        play_moves(&mut game, "4\nundo\nresign\nredo\n", false).unwrap();
+        assert_eq!(game.winner(), Some(Tile::O));
+        assert_eq!(game.moves_played(), 0);
+
+        let mut keypad = Game::new();
+        play_moves(&mut keypad, "5\n7\n3\n", true).unwrap();
+        assert!(keypad.same_board(&Game::from_moves(&[4, 0, 8]).unwrap()));
     }
-    println!("{game}");
-    match game.winner {
-        Tile::X => println!("Player X wins!"),
-        Tile::O => println!("Player O wins!"),
-        Tile::Empty => println!("It's a tie!"),
+
+    #[test]
+    fn test_analysis_of_a_position() {
+        let game = Game::from_notation("XX.OO.O.X").unwrap();
+        let analysis = analysis(&game);
+        assert!(analysis.contains("Best move for X: 2\n"));
+        assert!(analysis.contains("With perfect play: X wins\n"));
+        assert!(analysis.contains("X threats: 2\n"));
+        assert!(analysis.contains("O threats: 2, 5\n"));
     }
-    Ok(())
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_bell_only_for_a_win_on_a_terminal() {
+        let win = GameState::Win(Tile::X);
+        assert!(should_ring(true, true, win));
+        assert!(!should_ring(false, true, win));
+        assert!(!should_ring(true, false, win));
+        assert!(!should_ring(true, true, GameState::Draw));
+    }
 
     #[test]
-    fn test_row_is_complete() {
-        let row = Row {
-            tiles: [Tile::X, Tile::X, Tile::X],
-        };
-        assert!(row.is_complete(Tile::X));
+    fn test_clear_only_on_a_terminal() {
+        assert!(should_clear(true, true));
+        assert!(!should_clear(true, false));
+        assert!(!should_clear(false, true));
     }
 
     #[test]
-    fn test_diagonal_is_complete() {
-        let diag = Diagonal {
-            tiles: [&Tile::X, &Tile::X, &Tile::X],
-        };
-        assert!(diag.is_complete(Tile::X));
+    fn test_draw_offer_and_resignation() {
+        let (sender, receiver) = mpsc::channel();
+        for line in ["4", "draw", "no", "0", "draw", "accept"] {
+            sender.send(Ok(line.to_string())).unwrap();
+        }
+        let mut game = Game::new();
+        let round = play_round(&mut game, None, None, RoundOptions::default(), &receiver).unwrap();
+        assert_eq!(round, Round::Finished);
+        assert_eq!(game.state(), GameState::Draw);
+        assert_eq!(game.moves_played(), 2);
+
+        let mut game = Game::new();
+        play_moves(&mut game, "4\nresign\n", false).unwrap();
+        assert_eq!(game.winner(), Some(Tile::X));
+        let err = play_moves(&mut Game::new(), "accept\n", false).unwrap_err();
+        assert_eq!(err.line, 1);
     }
 
     #[test]
-    fn test_game_is_complete() {
+    fn test_quit_abandons_the_round() {
+        let (sender, receiver) = mpsc::channel();
+        sender.send(Ok("4".to_string())).unwrap();
+        sender.send(Ok("quit".to_string())).unwrap();
         let mut game = Game::new();
-        game.play(0);
-        game.play(1);
-        game.play(3);
-        game.play(2);
-        game.play(6);
-        assert!(game.is_complete());
+        let round = play_round(&mut game, None, None, RoundOptions::default(), &receiver).unwrap();
+        assert_eq!(round, Round::Abandoned);
+        assert_eq!(game.winner(), None);
     }
+
     #[test]
-    fn test_game_is_tie() {
+    fn test_round_ends_when_input_closes() {
+        let (sender, receiver) = mpsc::channel();
+        sender.send(Ok("4".to_string())).unwrap();
+        drop(sender);
         let mut game = Game::new();
-        game.play(0);
-        game.play(1);
-        game.play(2);
-        game.play(3);
-        game.play(4);
-        game.play(5);
-        game.play(6);
-        game.play(7);
-        game.play(8);
-        assert!(game.is_tie());
+        let round = play_round(&mut game, None, None, RoundOptions::default(), &receiver).unwrap();
+        assert_eq!(round, Round::InputClosed);
+        assert_eq!(game.moves_played(), 1);
     }
 }
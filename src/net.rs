@@ -0,0 +1,150 @@
+use core::fmt::{Display, Formatter};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+
+// One message per line. The host greets the client with "hello <size>", then each
+// side sends "move <square>" on its turn, or "resign" to give up
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Message {
+    Hello { size: usize },
+    Move(usize),
+    Resign,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum DecodeError {
+    Unknown(String),
+    InvalidNumber(String),
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            Self::Unknown(line) => write!(f, "Unknown message '{line}'"),
+            Self::InvalidNumber(n) => write!(f, "'{n}' is not a number"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+pub fn encode(message: Message) -> String {
+    match message {
+        Message::Hello { size } => format!("hello {size}\n"),
+        Message::Move(index) => format!("move {index}\n"),
+        Message::Resign => "resign\n".to_string(),
+    }
+}
+
+pub fn decode(line: &str) -> Result<Message, DecodeError> {
+    let line = line.trim();
+    let number = |n: &str| {
+        n.trim()
+            .parse()
+            .map_err(|_| DecodeError::InvalidNumber(n.to_string()))
+    };
+    match line.split_once(' ') {
+        Some(("hello", size)) => Ok(Message::Hello {
+            size: number(size)?,
+        }),
+        Some(("move", index)) => Ok(Message::Move(number(index)?)),
+        None if line == "resign" => Ok(Message::Resign),
+        _ => Err(DecodeError::Unknown(line.to_string())),
+    }
+}
+
+// A TCP link to the other player. Each side keeps its own Game and checks every
+// move it receives with play(), so the rules live in one place
+pub struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Connection {
+    // Waits for a single client to connect
+    pub fn host(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let (stream, _) = TcpListener::bind(addr)?.accept()?;
+        Self::new(stream)
+    }
+
+    pub fn connect(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        Self::new(TcpStream::connect(addr)?)
+    }
+
+    fn new(stream: TcpStream) -> io::Result<Self> {
+        Ok(Self {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+        })
+    }
+
+    pub fn send(&mut self, message: Message) -> io::Result<()> {
+        self.writer.write_all(encode(message).as_bytes())
+    }
+
+    // Blocks for the next message. A closed connection is UnexpectedEof and a line
+    // that isn't a message is InvalidData
+    pub fn receive(&mut self) -> io::Result<Message> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        decode(&line).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode_round_trip() {
+        for message in [
+            Message::Hello { size: 3 },
+            Message::Move(0),
+            Message::Move(8),
+            Message::Resign,
+        ] {
+            let line = encode(message);
+            assert!(line.ends_with('\n'));
+            assert_eq!(decode(&line), Ok(message));
+        }
+        assert_eq!(decode("move 4\r\n"), Ok(Message::Move(4)));
+    }
+
+    #[test]
+    fn test_decode_rejects_garbage() {
+        assert_eq!(
+            decode("move four"),
+            Err(DecodeError::InvalidNumber("four".to_string()))
+        );
+        assert_eq!(
+            decode("move -1").unwrap_err().to_string(),
+            "'-1' is not a number"
+        );
+        assert_eq!(decode(""), Err(DecodeError::Unknown(String::new())));
+        assert_eq!(
+            decode("castle 4"),
+            Err(DecodeError::Unknown("castle 4".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_messages_cross_a_loopback_connection() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = std::thread::spawn(move || {
+            let mut client = Connection::connect(addr).unwrap();
+            client.send(Message::Move(4)).unwrap();
+            client.receive().unwrap()
+        });
+        let mut host = Connection::new(listener.accept().unwrap().0).unwrap();
+        assert_eq!(host.receive().unwrap(), Message::Move(4));
+        host.send(Message::Resign).unwrap();
+        assert_eq!(client.join().unwrap(), Message::Resign);
+        assert_eq!(
+            host.receive().unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+}
@@ -0,0 +1,392 @@
+use crate::{Game, GameState, Tile};
+use core::fmt::{Display, Formatter};
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum ParseError {
+    InvalidLength(usize),
+    InvalidCell(char),
+    InvalidPlayer(String),
+    InvalidTile(String),
+    InvalidMoveNumber(String),
+    InvalidSquare(String),
+    IllegalPosition(&'static str),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            Self::InvalidLength(len) => write!(f, "{len} cells do not make a square board"),
+            Self::InvalidCell(c) => write!(f, "'{c}' is not a valid cell, use X, O or ."),
+            Self::InvalidPlayer(p) => write!(f, "'{p}' is not a valid player, use X or O"),
+            Self::InvalidTile(t) => write!(f, "'{t}' is not a valid tile, use X, O or ."),
+            Self::InvalidMoveNumber(n) => {
+                write!(f, "Expected a move number like '1.' but found '{n}'")
+            }
+            Self::InvalidSquare(s) => write!(f, "'{s}' is not a square on the board"),
+            Self::IllegalPosition(reason) => write!(f, "That position can't happen: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// Parses a log like "1. a1 c3 2. b2", with files from the left and ranks from the
+// bottom as in chess, into square indexes for a size x size board
+pub fn parse_move_log(s: &str, size: usize) -> Result<Vec<usize>, ParseError> {
+    let mut moves = Vec::new();
+    let mut tokens = s.split_whitespace().peekable();
+    let mut number = 1;
+    while let Some(token) = tokens.next() {
+        let numbered = token.strip_suffix('.').and_then(|n| n.parse().ok()) == Some(number);
+        let squares: Vec<&str> = (0..2)
+            .map_while(|_| tokens.next_if(|token| !token.ends_with('.')))
+            .collect();
+        if !numbered || squares.is_empty() {
+            return Err(ParseError::InvalidMoveNumber(token.to_string()));
+        }
+        for square in &squares {
+            moves.push(parse_square(square, size)?);
+        }
+        // Only the last move number may be missing O's reply
+        if let (1, Some(next)) = (squares.len(), tokens.peek()) {
+            return Err(ParseError::InvalidMoveNumber((*next).to_string()));
+        }
+        number += 1;
+    }
+    Ok(moves)
+}
+
+fn parse_square(square: &str, size: usize) -> Result<usize, ParseError> {
+    let invalid = || ParseError::InvalidSquare(square.to_string());
+    let mut chars = square.chars();
+    let file = chars.next().ok_or_else(invalid)?;
+    let col = file
+        .is_ascii_lowercase()
+        .then(|| usize::from(file as u8 - b'a'))
+        .filter(|&col| col < size)
+        .ok_or_else(invalid)?;
+    let rank: usize = chars.as_str().parse().map_err(|_| invalid())?;
+    if rank == 0 || rank > size {
+        return Err(invalid());
+    }
+    Ok((size - rank) * size + col)
+}
+
+impl Game {
+    // The history as a chess-style move log, see parse_move_log
+    pub fn move_log(&self) -> String {
+        self.history
+            .chunks(2)
+            .enumerate()
+            .map(|(number, pair)| {
+                let squares: Vec<String> =
+                    pair.iter().map(|&index| self.square_name(index)).collect();
+                format!("{}. {}", number + 1, squares.join(" "))
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn square_name(&self, index: usize) -> String {
+        let file = char::from(
+            b'a' + u8::try_from(index % self.width).expect("boards are at most 255 wide"),
+        );
+        format!("{file}{}", self.height - index / self.width)
+    }
+
+    // Row-major cells with '.' for empty, then a space and the player to move
+    pub fn to_notation(&self) -> String {
+        let cells: String = self
+            .board
+            .iter()
+            .flat_map(|row| row.tiles.iter())
+            .map(|&tile| notation_char(tile))
+            .collect();
+        format!("{cells} {}", self.player)
+    }
+
+    // The board on one line for logs, like to_notation() but with '|' between rows
+    pub fn summary_line(&self) -> String {
+        self.board
+            .iter()
+            .map(|row| {
+                row.tiles
+                    .iter()
+                    .map(|&tile| notation_char(tile))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("|")
+    }
+
+    pub fn from_notation(s: &str) -> Result<Self, ParseError> {
+        let (cells, player) = match s.trim().split_once(' ') {
+            Some((cells, player)) => (cells, Some(player.trim())),
+            None => (s.trim(), None),
+        };
+        let tiles = cells
+            .chars()
+            .map(|c| match c {
+                'X' => Ok(Tile::X),
+                'O' => Ok(Tile::O),
+                'Y' => Ok(Tile::Y),
+                '.' => Ok(Tile::Empty),
+                _ => Err(ParseError::InvalidCell(c)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::load(&tiles, player)
+    }
+
+    // Sets up a square board straight from its cells in row-major order, working out
+    // whose turn it is and whether the game is already over
+    pub fn from_cells(cells: &[Tile]) -> Result<Self, ParseError> {
+        Self::load(cells, None)
+    }
+
+    fn load(tiles: &[Tile], player: Option<&str>) -> Result<Self, ParseError> {
+        let size = (1..=15)
+            .find(|size| size * size == tiles.len())
+            .ok_or(ParseError::InvalidLength(tiles.len()))?;
+
+        let mut game = Self::with_size(size);
+        for (row, chunk) in game.board.iter_mut().zip(tiles.chunks(size)) {
+            row.tiles.copy_from_slice(chunk);
+        }
+        let filled = tiles.iter().filter(|&&t| t.is_occupied()).count();
+        game.turn = u8::try_from(filled).expect("board size is capped to fit a u8");
+        if tiles.contains(&Tile::Y) {
+            game.set_players(3);
+        }
+        // X opens unless the counts, or the player to move, say O did
+        let mut starts = Vec::new();
+        for first in [Tile::X, Tile::O] {
+            game.starter = first;
+            if game.marks_alternate() {
+                starts.push((first, game.expected_player()));
+            }
+        }
+        if starts.is_empty() {
+            return Err(ParseError::IllegalPosition(
+                "the players take turns, so the first has as many marks as the next or one more",
+            ));
+        }
+        let completed: Vec<Tile> = game
+            .lines()
+            .filter(|line| line[0].is_occupied() && line.iter().all(|&t| t == line[0]))
+            .map(|line| line[0])
+            .collect();
+        if completed.iter().any(|&tile| tile != completed[0]) {
+            return Err(ParseError::IllegalPosition(
+                "X and O can't both have a line",
+            ));
+        }
+        let (starter, to_move) = match player {
+            None => starts[0],
+            Some(mark) => match mark.parse() {
+                Ok(tile) if game.marks().contains(&tile) => *starts
+                    .iter()
+                    .find(|&&(_, next)| next == tile)
+                    .ok_or(ParseError::IllegalPosition(
+                        "the player to move doesn't match the marks on the board",
+                    ))?,
+                _ => return Err(ParseError::InvalidPlayer(mark.to_string())),
+            },
+        };
+        game.starter = starter;
+        game.player = to_move;
+
+        game.state = if let Some(winner) = game.winner_on_board() {
+            GameState::Win(winner)
+        } else if game.is_tie() || game.is_forced_draw() {
+            GameState::Draw
+        } else {
+            GameState::InProgress
+        };
+        if !game.is_valid() {
+            return Err(ParseError::IllegalPosition(
+                "the board doesn't add up to a real game",
+            ));
+        }
+        Ok(game)
+    }
+}
+
+const fn notation_char(tile: Tile) -> char {
+    match tile {
+        Tile::X => 'X',
+        Tile::O => 'O',
+        Tile::Y => 'Y',
+        Tile::Empty => '.',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WinningLine;
+
+    #[test]
+    fn test_notation_mid_game() {
+        let game = Game::from_notation("XO..X.... O").unwrap();
+        assert_eq!(game.turn, 3);
+        assert_eq!(game.current_player(), Tile::O);
+        assert_eq!(game.state(), GameState::InProgress);
+        assert_eq!(game.board[1].tiles, vec![Tile::Empty, Tile::X, Tile::Empty]);
+        assert_eq!(game.to_notation(), "XO..X.... O");
+    }
+
+    #[test]
+    fn test_notation_o_first_round_trip() {
+        let mut game = Game::new_with_starter(Tile::O);
+        game.play(4).unwrap();
+        let notation = game.to_notation();
+        assert_eq!(notation, "....O.... X");
+        let loaded = Game::from_notation(&notation).unwrap();
+        assert_eq!(loaded.starter(), Tile::O);
+        assert_eq!(loaded.current_player(), Tile::X);
+        assert_eq!(loaded.to_notation(), notation);
+
+        let ahead = Game::from_notation("OO.X.....").unwrap();
+        assert_eq!(ahead.starter(), Tile::O);
+        assert_eq!(ahead.current_player(), Tile::X);
+        let empty = Game::from_notation("......... O").unwrap();
+        assert_eq!(empty.starter(), Tile::O);
+        assert_eq!(Game::from_notation("XO.......").unwrap().starter(), Tile::X);
+        assert_eq!(
+            Game::from_notation("XO....... O").unwrap().current_player(),
+            Tile::O
+        );
+    }
+
+    #[test]
+    fn test_summary_line() {
+        assert_eq!(Game::new().summary_line(), "...|...|...");
+        let game = Game::from_moves(&[0, 2, 4, 6, 8]).unwrap();
+        assert_eq!(game.summary_line(), "X.O|.X.|O.X");
+    }
+
+    #[test]
+    fn test_notation_three_players() {
+        let game = Game::from_notation("XOY.XO..........").unwrap();
+        assert_eq!(game.players(), 3);
+        assert_eq!(game.current_player(), Tile::Y);
+        assert_eq!(game.to_notation(), "XOY.XO.......... Y");
+        assert!(matches!(
+            Game::from_notation("XY.............."),
+            Err(ParseError::IllegalPosition(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_cells() {
+        use Tile::{Empty as E, O, X};
+        let mut game = Game::from_cells(&[X, X, E, O, O, E, E, E, E]).unwrap();
+        assert_eq!(game.current_player(), Tile::X);
+        assert_eq!(game.turn(), 4);
+        game.play(2).unwrap();
+        assert_eq!(game.winner(), Some(Tile::X));
+
+        let won = Game::from_cells(&[O, O, O, X, X, E, X, E, E]).unwrap();
+        assert_eq!(won.winner(), Some(Tile::O));
+        assert!(Game::from_cells(&[X, X, X, E, E, E, E, E, E]).is_err());
+        assert!(Game::from_cells(&[X, E, E]).is_err());
+    }
+
+    #[test]
+    fn test_notation_detects_win() {
+        let game = Game::from_notation("XXXOO....").unwrap();
+        assert_eq!(game.state(), GameState::Win(Tile::X));
+        assert!(game.game_over());
+    }
+
+    #[test]
+    fn test_notation_loads_win_for_o() {
+        let game = Game::from_notation("XX.OOOX.. X").unwrap();
+        assert_eq!(game.winner_on_board(), Some(Tile::O));
+        assert_eq!(game.winner(), Some(Tile::O));
+        assert_eq!(game.winning_line(), Some(WinningLine::Row(1)));
+    }
+
+    #[test]
+    fn test_move_log_round_trip() {
+        let game = Game::from_moves(&[6, 2, 4, 0, 1]).unwrap();
+        let log = game.move_log();
+        assert_eq!(log, "1. a1 c3 2. b2 a3 3. b3");
+        assert_eq!(parse_move_log(&log, 3), Ok(vec![6, 2, 4, 0, 1]));
+        assert_eq!(Game::new().move_log(), "");
+    }
+
+    #[test]
+    fn test_move_log_rejects_invalid() {
+        assert_eq!(
+            parse_move_log("1. a1 c3 3. b2", 3),
+            Err(ParseError::InvalidMoveNumber("3.".to_string()))
+        );
+        assert_eq!(
+            parse_move_log("1. a1 2. b2", 3),
+            Err(ParseError::InvalidMoveNumber("2.".to_string()))
+        );
+        assert_eq!(
+            parse_move_log("a1 c3", 3),
+            Err(ParseError::InvalidMoveNumber("a1".to_string()))
+        );
+        assert_eq!(
+            parse_move_log("1. d1", 3),
+            Err(ParseError::InvalidSquare("d1".to_string()))
+        );
+        assert_eq!(
+            parse_move_log("1. a4", 3),
+            Err(ParseError::InvalidSquare("a4".to_string()))
+        );
+        assert_eq!(
+            parse_move_log("1. a1 c3 b2", 3),
+            Err(ParseError::InvalidMoveNumber("b2".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_notation_rejects_invalid() {
+        assert_eq!(
+            Game::from_notation("XO..X...").err(),
+            Some(ParseError::InvalidLength(8))
+        );
+        assert_eq!(
+            Game::from_notation("XO..Z....").err(),
+            Some(ParseError::InvalidCell('Z'))
+        );
+        assert_eq!(
+            Game::from_notation("XO..X.... Q").err(),
+            Some(ParseError::InvalidPlayer("Q".to_string()))
+        );
+        assert_eq!(
+            Game::from_notation("XO..X.... X").err(),
+            Some(ParseError::IllegalPosition(
+                "the player to move doesn't match the marks on the board"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_notation_rejects_illegal_positions() {
+        assert_eq!(
+            Game::from_notation("XXXOOO..X").err(),
+            Some(ParseError::IllegalPosition(
+                "X and O can't both have a line"
+            ))
+        );
+        assert!(matches!(
+            Game::from_notation("XXX......"),
+            Err(ParseError::IllegalPosition(_))
+        ));
+        assert!(matches!(
+            Game::from_notation("OO......."),
+            Err(ParseError::IllegalPosition(_))
+        ));
+        for late_move in ["XX.OOOX.X", "XX.OOOX.X O"] {
+            assert!(matches!(
+                Game::from_notation(late_move),
+                Err(ParseError::IllegalPosition(_))
+            ));
+        }
+    }
+}
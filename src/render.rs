@@ -0,0 +1,333 @@
+use crate::{Game, GameState, Tile};
+use colored::{Color, ColoredString, Colorize};
+use core::fmt::{Display, Formatter, Write};
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Theme {
+    pub x: String,
+    pub x_color: Option<Color>,
+    pub o: String,
+    pub o_color: Option<Color>,
+    pub y: String,
+    pub y_color: Option<Color>,
+    // None shows the square's index instead, or a blank when labels are on
+    pub empty: Option<String>,
+    pub empty_color: Option<Color>,
+    // Column numbers above the board and row numbers down its left side
+    pub labels: bool,
+    // Draws O in bold so the marks differ by more than colour
+    pub bold_o: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            x: "X".to_string(),
+            x_color: Some(Color::Green),
+            o: "O".to_string(),
+            o_color: Some(Color::Red),
+            y: "Y".to_string(),
+            y_color: Some(Color::Blue),
+            empty: None,
+            empty_color: None,
+            labels: false,
+            bold_o: false,
+        }
+    }
+}
+
+impl Theme {
+    // Blue and yellow stay apart under red-green colour blindness
+    pub fn color_blind() -> Self {
+        Self {
+            x_color: Some(Color::Blue),
+            o_color: Some(Color::Yellow),
+            y_color: Some(Color::Magenta),
+            bold_o: true,
+            ..Self::default()
+        }
+    }
+}
+
+impl Game {
+    pub fn render_plain(&self) -> String {
+        let mut out = String::new();
+        self.write_board(&mut out, false)
+            .expect("writing to a String never fails");
+        out
+    }
+
+    // The board drawn with box-drawing characters, with every cell padded to line up
+    pub fn render_boxed(&self) -> String {
+        let highlighted = self.highlighted();
+        let cells: Vec<(String, ColoredString)> = (0..self.cell_count())
+            .map(|index| self.cell(index, &highlighted))
+            .collect();
+        let width = cells
+            .iter()
+            .map(|(symbol, _)| symbol.chars().count())
+            .max()
+            .unwrap_or(1);
+        let border = |left: char, middle: char, right: char| {
+            let segment = "─".repeat(width + 2);
+            let segments = vec![segment; self.width];
+            format!("{left}{}{right}\n", segments.join(&middle.to_string()))
+        };
+
+        let mut out = border('┌', '┬', '┐');
+        for (row, chunk) in cells.chunks(self.width).enumerate() {
+            if row > 0 {
+                out.push_str(&border('├', '┼', '┤'));
+            }
+            for (symbol, styled) in chunk {
+                let padding = " ".repeat(width - symbol.chars().count());
+                out.push_str(&format!("│ {styled}{padding} "));
+            }
+            out.push_str("│\n");
+        }
+        out.push_str(&border('└', '┴', '┘'));
+        out
+    }
+
+    fn write_board(&self, f: &mut impl Write, color: bool) -> std::fmt::Result {
+        let highlighted = self.highlighted();
+        let cells: Vec<(String, ColoredString)> = (0..self.cell_count())
+            .map(|index| self.cell(index, &highlighted))
+            .collect();
+        // Every cell is padded to the widest symbol, or column number when labelled,
+        // so square 10 and up line up with the rest
+        let label_width = if self.theme.labels {
+            (self.width - 1).to_string().len()
+        } else {
+            0
+        };
+        let cell_width = cells
+            .iter()
+            .map(|(symbol, _)| symbol.chars().count())
+            .fold(label_width, usize::max);
+        let rule = "-".repeat((cell_width + 1) * self.width - 1);
+        let margin = if self.theme.labels {
+            (self.height - 1).to_string().len() + 1
+        } else {
+            0
+        };
+        if self.theme.labels {
+            write!(f, "{:margin$}", "")?;
+            for col in 0..self.width {
+                write!(f, "{col:<cell_width$} ")?;
+            }
+            writeln!(f)?;
+        }
+        for row in 0..self.height {
+            if self.theme.labels {
+                write!(f, "{row:<margin$}")?;
+            }
+            for (symbol, styled) in &cells[row * self.width..(row + 1) * self.width] {
+                let padding = " ".repeat(cell_width - symbol.chars().count());
+                if color {
+                    write!(f, "{styled}{padding} ")
+                } else {
+                    write!(f, "{symbol}{padding} ")
+                }?;
+            }
+            writeln!(f)?;
+            writeln!(f, "{:margin$}{rule}", "")?;
+        }
+        Ok(())
+    }
+
+    fn highlighted(&self) -> Vec<usize> {
+        match self.state {
+            GameState::Win(_) => self.winning_cells().unwrap_or_default(),
+            GameState::InProgress | GameState::Draw => Vec::new(),
+        }
+    }
+
+    // A square's plain symbol, and the same symbol in its theme colour with any win highlight
+    fn cell(&self, index: usize, highlighted: &[usize]) -> (String, ColoredString) {
+        let theme = &self.theme;
+        let (symbol, symbol_color) = match self.tile_at(index) {
+            Tile::X => (theme.x.clone(), theme.x_color),
+            Tile::O => (theme.o.clone(), theme.o_color),
+            Tile::Y => (theme.y.clone(), theme.y_color),
+            Tile::Empty => {
+                let hint = if theme.labels {
+                    " ".to_string()
+                } else {
+                    index.to_string()
+                };
+                let symbol = theme.empty.clone().unwrap_or(hint);
+                (symbol, theme.empty_color)
+            }
+        };
+        let styled = match symbol_color {
+            Some(symbol_color) if theme.bold_o && self.tile_at(index) == Tile::O => {
+                symbol.as_str().color(symbol_color).bold()
+            }
+            Some(symbol_color) => symbol.as_str().color(symbol_color),
+            // Dim the index hints so the marks stand out
+            None if self.tile_at(index).is_empty() && theme.empty.is_none() => {
+                symbol.as_str().dimmed()
+            }
+            None => symbol.as_str().normal(),
+        };
+        if highlighted.contains(&index) {
+            (symbol, styled.bold().underline())
+        } else {
+            (symbol, styled)
+        }
+    }
+}
+
+impl Display for Game {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        self.write_board(f, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play_all(moves: &[usize]) -> Game {
+        let mut game = Game::new();
+        for &index in moves {
+            game.play(index).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn test_display_highlights_only_winning_cells() {
+        colored::control::set_override(true);
+        let highlighted = "X".green().bold().underline().to_string();
+        let plain = format!("{} ", "X".green());
+
+        let game = play_all(&[3, 0, 4, 1, 8, 6]);
+        assert!(!format!("{game}").contains(&highlighted));
+
+        let game = play_all(&[3, 0, 4, 1, 8, 6, 5]);
+        let rendered = format!("{game}");
+        assert_eq!(game.winning_cells(), Some(vec![3, 4, 5]));
+        assert_eq!(rendered.matches(&highlighted).count(), 3);
+        assert_eq!(rendered.matches(&plain).count(), 1);
+    }
+
+    #[test]
+    fn test_index_hints_are_dimmed() {
+        colored::control::set_override(true);
+        let game = play_all(&[0]);
+        let rendered = format!("{game}");
+        assert!(rendered.contains(&"1".dimmed().to_string()));
+        assert!(rendered.contains(&"8".dimmed().to_string()));
+        assert!(!game.render_plain().contains('\x1b'));
+    }
+
+    #[test]
+    fn test_custom_theme_swaps_symbols() {
+        let mut game = play_all(&[0, 4]);
+        game.set_theme(Theme {
+            x: "O".to_string(),
+            o: "X".to_string(),
+            empty: Some(".".to_string()),
+            ..Theme::default()
+        });
+        assert!(game.render_plain().starts_with("O . . \n-----\n. X . \n"));
+
+        colored::control::set_override(true);
+        game.set_theme(Theme {
+            x_color: Some(Color::Blue),
+            ..Theme::default()
+        });
+        assert!(format!("{game}").contains(&"X".blue().to_string()));
+    }
+
+    #[test]
+    fn test_color_blind_theme() {
+        colored::control::set_override(true);
+        let mut game = play_all(&[0, 4]);
+        let default = format!("{game}");
+        game.set_theme(Theme::color_blind());
+        let color_blind = format!("{game}");
+        assert_ne!(default, color_blind);
+        assert!(!color_blind.contains(&"X".green().to_string()));
+        assert!(!color_blind.contains(&"O".red().to_string()));
+        assert!(color_blind.contains(&"X".blue().to_string()));
+        assert!(color_blind.contains(&"O".yellow().bold().to_string()));
+    }
+
+    #[test]
+    fn test_coordinate_labels() {
+        let mut game = play_all(&[0, 4]);
+        let unlabelled = game.render_plain();
+        assert!(unlabelled.starts_with("X 1 2 \n-----\n"));
+
+        game.set_theme(Theme {
+            labels: true,
+            ..Theme::default()
+        });
+        let labelled = game.render_plain();
+        let lines: Vec<&str> = labelled.lines().collect();
+        assert_eq!(lines[0], "  0 1 2 ");
+        assert_eq!(lines[1], "0 X     ");
+        assert_eq!(lines[2], "  -----");
+        assert_eq!(lines[3], "1   O   ");
+        assert!(!unlabelled.contains("0 1 2"));
+    }
+
+    #[test]
+    fn test_separator_spans_the_row() {
+        let game = Game::with_size(4);
+        let plain = game.render_plain();
+        let lines: Vec<&str> = plain.lines().collect();
+        assert_eq!(lines[0], "0  1  2  3  ");
+        assert_eq!(lines[1], "-----------");
+        assert_eq!(lines[1].len(), lines[6].trim_end().len());
+    }
+
+    #[test]
+    fn test_large_boards_show_every_square_number() {
+        let game = Game::with_size(4);
+        let plain = game.render_plain();
+        let lines: Vec<&str> = plain.lines().collect();
+        assert_eq!(lines[2], "4  5  6  7  ");
+        assert_eq!(lines[4], "8  9  10 11 ");
+        assert_eq!(lines[6], "12 13 14 15 ");
+
+        let mut game = Game::with_size(11);
+        game.set_theme(Theme {
+            labels: true,
+            ..Theme::default()
+        });
+        let labelled = game.render_plain();
+        let lines: Vec<&str> = labelled.lines().collect();
+        assert!(lines[0].starts_with("   0  1  2 "));
+        assert!(lines[0].ends_with(" 9  10 "));
+        assert_eq!(lines[2].trim_start().len(), 3 * 11 - 1);
+    }
+
+    #[test]
+    fn test_render_boxed() {
+        let game = play_all(&[0, 4]);
+        let boxed = game.render_boxed();
+        let lines: Vec<&str> = boxed.lines().collect();
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[0], "┌───┬───┬───┐");
+        assert_eq!(lines[2], "├───┼───┼───┤");
+        assert_eq!(lines[6], "└───┴───┴───┘");
+        assert_eq!(boxed.matches('│').count(), 12);
+        assert!(lines
+            .iter()
+            .all(|line| line.starts_with(['┌', '├', '└', '│'])));
+    }
+
+    #[test]
+    fn test_render_plain_has_no_ansi() {
+        colored::control::set_override(true);
+        let game = play_all(&[0, 3, 1, 4, 2]);
+        let plain = game.render_plain();
+        assert!(!plain.contains('\x1b'));
+        assert!(plain.starts_with("X X X \n-----\n"));
+        assert!(format!("{game}").contains('\x1b'));
+    }
+}
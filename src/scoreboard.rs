@@ -0,0 +1,75 @@
+use crate::{GameState, Tile};
+use core::fmt::{Display, Formatter};
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct Scoreboard {
+    pub x_wins: u32,
+    pub o_wins: u32,
+    pub draws: u32,
+}
+
+impl Scoreboard {
+    pub fn record(&mut self, state: GameState) {
+        match state {
+            GameState::Win(Tile::X) => self.x_wins += 1,
+            GameState::Win(Tile::O) => self.o_wins += 1,
+            GameState::Draw => self.draws += 1,
+            // Three-player games aren't kept score of
+            GameState::Win(Tile::Y | Tile::Empty) | GameState::InProgress => {}
+        }
+    }
+
+    pub const fn wins(&self, tile: Tile) -> u32 {
+        match tile {
+            Tile::X => self.x_wins,
+            Tile::O => self.o_wins,
+            Tile::Y | Tile::Empty => 0,
+        }
+    }
+
+    // The side that has reached `target` wins first, if any
+    pub const fn leader(&self, target: u32) -> Option<Tile> {
+        if self.x_wins >= target {
+            Some(Tile::X)
+        } else if self.o_wins >= target {
+            Some(Tile::O)
+        } else {
+            None
+        }
+    }
+}
+
+impl Display for Scoreboard {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "X {} - {} O ({} draws)",
+            self.x_wins, self.o_wins, self.draws
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_totals() {
+        let mut scoreboard = Scoreboard::default();
+        scoreboard.record(GameState::Win(Tile::X));
+        scoreboard.record(GameState::Draw);
+        scoreboard.record(GameState::Win(Tile::X));
+        scoreboard.record(GameState::InProgress);
+        assert_eq!(
+            scoreboard,
+            Scoreboard {
+                x_wins: 2,
+                o_wins: 0,
+                draws: 1
+            }
+        );
+        assert_eq!(scoreboard.to_string(), "X 2 - 0 O (1 draws)");
+        assert_eq!(scoreboard.leader(2), Some(Tile::X));
+        assert_eq!(scoreboard.leader(3), None);
+    }
+}
@@ -0,0 +1,118 @@
+use quick_start::ai::{self, Ai, Difficulty};
+use quick_start::{GameState, Scoreboard, Tile};
+
+// Results between two players split by which of them moved first. X always
+// moves first, so in each scoreboard x_wins are the starter's wins
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct FirstMoveStats {
+    pub first_starts: Scoreboard,
+    pub second_starts: Scoreboard,
+}
+
+impl FirstMoveStats {
+    pub fn record(&mut self, starter: Starter, state: GameState) {
+        match starter {
+            Starter::First => self.first_starts.record(state),
+            Starter::Second => self.second_starts.record(state),
+        }
+    }
+
+    pub fn table(&self, first: &str, second: &str) -> String {
+        let row = |name: &str, scoreboard: &Scoreboard| {
+            let games = scoreboard.x_wins + scoreboard.o_wins + scoreboard.draws;
+            let percent = |count: u32| {
+                if games == 0 {
+                    0.0
+                } else {
+                    f64::from(count) * 100.0 / f64::from(games)
+                }
+            };
+            format!(
+                "{name:<14}{games:>6}{:>9.1}%{:>9.1}%{:>9.1}%\n",
+                percent(scoreboard.wins(Tile::X)),
+                percent(scoreboard.wins(Tile::O)),
+                percent(scoreboard.draws),
+            )
+        };
+        let mut out = format!(
+            "{:<14}{:>6}{:>10}{:>10}{:>10}\n",
+            "First move", "Games", "Starter", "Other", "Draws"
+        );
+        out.push_str(&row(first, &self.first_starts));
+        out.push_str(&row(second, &self.second_starts));
+        out
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Starter {
+    First,
+    Second,
+}
+
+// Plays `games` self-play games, swapping who gets X, and so the first move, each game
+pub fn first_move_stats(
+    first: Difficulty,
+    second: Difficulty,
+    games: u32,
+    seed: u64,
+) -> FirstMoveStats {
+    let mut first_ai = Ai::seeded(seed);
+    let mut second_ai = Ai::seeded(seed.wrapping_add(1));
+    let mut stats = FirstMoveStats::default();
+    for game in 0..games {
+        if game % 2 == 0 {
+            let state = ai::self_play(&mut first_ai, first, &mut second_ai, second);
+            stats.record(Starter::First, state);
+        } else {
+            let state = ai::self_play(&mut second_ai, second, &mut first_ai, first);
+            stats.record(Starter::Second, state);
+        }
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_results_go_to_the_starters_bucket() {
+        let mut stats = FirstMoveStats::default();
+        stats.record(Starter::First, GameState::Win(Tile::X));
+        stats.record(Starter::First, GameState::Draw);
+        stats.record(Starter::Second, GameState::Win(Tile::X));
+        stats.record(Starter::Second, GameState::Win(Tile::O));
+        stats.record(Starter::Second, GameState::Win(Tile::X));
+        assert_eq!(
+            stats.first_starts,
+            Scoreboard {
+                x_wins: 1,
+                o_wins: 0,
+                draws: 1
+            }
+        );
+        assert_eq!(
+            stats.second_starts,
+            Scoreboard {
+                x_wins: 2,
+                o_wins: 1,
+                draws: 0
+            }
+        );
+
+        let table = stats.table("easy", "hard");
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].starts_with("easy"));
+        assert!(lines[1].contains("50.0%"));
+        assert!(lines[2].contains("66.7%"));
+    }
+
+    #[test]
+    fn test_games_alternate_starters() {
+        let stats = first_move_stats(Difficulty::Hard, Difficulty::Hard, 10, 1);
+        assert_eq!(stats.first_starts.draws, 5);
+        assert_eq!(stats.second_starts.draws, 5);
+    }
+}
@@ -0,0 +1,183 @@
+use std::io::{self, Write};
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::style::{Print, PrintStyledContent, Stylize};
+use crossterm::{cursor, execute, queue, terminal};
+use quick_start::{Game, GameState, Tile};
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+// Arrow keys, or hjkl for vi users
+pub const fn direction(code: KeyCode) -> Option<Direction> {
+    match code {
+        KeyCode::Up | KeyCode::Char('k') => Some(Direction::Up),
+        KeyCode::Down | KeyCode::Char('j') => Some(Direction::Down),
+        KeyCode::Left | KeyCode::Char('h') => Some(Direction::Left),
+        KeyCode::Right | KeyCode::Char('l') => Some(Direction::Right),
+        _ => None,
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct Cursor {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Cursor {
+    // One square over, staying put at the edges of a width x height board
+    pub fn moved(self, direction: Direction, width: usize, height: usize) -> Self {
+        match direction {
+            Direction::Up => Self {
+                row: self.row.saturating_sub(1),
+                ..self
+            },
+            Direction::Down => Self {
+                row: (self.row + 1).min(height - 1),
+                ..self
+            },
+            Direction::Left => Self {
+                col: self.col.saturating_sub(1),
+                ..self
+            },
+            Direction::Right => Self {
+                col: (self.col + 1).min(width - 1),
+                ..self
+            },
+        }
+    }
+
+    pub const fn index(self, width: usize) -> usize {
+        self.row * width + self.col
+    }
+}
+
+// Plays a game in the terminal's alternate screen. `computer_move` is asked before
+// every turn and plays for that side when it returns a square
+pub fn run(
+    game: &mut Game,
+    mut computer_move: impl FnMut(&Game) -> Option<usize>,
+) -> io::Result<()> {
+    terminal::enable_raw_mode()?;
+    let mut out = io::stdout();
+    execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+    let result = event_loop(game, &mut computer_move, &mut out);
+    execute!(out, cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+}
+
+fn event_loop(
+    game: &mut Game,
+    computer_move: &mut impl FnMut(&Game) -> Option<usize>,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let mut cursor = Cursor::default();
+    loop {
+        if !game.game_over() {
+            if let Some(index) = computer_move(game) {
+                game.play(index).expect("computer picked an illegal move");
+                continue;
+            }
+        }
+        draw(game, cursor, out)?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        if game.game_over() {
+            return Ok(());
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            // Taken squares are simply ignored
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                let _ = game.play(cursor.index(game.width()));
+            }
+            code => {
+                if let Some(direction) = direction(code) {
+                    cursor = cursor.moved(direction, game.width(), game.height());
+                }
+            }
+        }
+    }
+}
+
+fn draw(game: &Game, cursor: Cursor, out: &mut impl Write) -> io::Result<()> {
+    queue!(
+        out,
+        terminal::Clear(terminal::ClearType::All),
+        cursor::MoveTo(0, 0)
+    )?;
+    let winning = match game.state() {
+        GameState::Win(_) => game.winning_cells().unwrap_or_default(),
+        GameState::InProgress | GameState::Draw => Vec::new(),
+    };
+    for (row, tiles) in game.grid().iter().enumerate() {
+        for (col, &tile) in tiles.iter().enumerate() {
+            let mut symbol = match tile {
+                Tile::X => "X".green(),
+                Tile::O => "O".red(),
+                Tile::Y => "Y".blue(),
+                Tile::Empty => "·".dark_grey(),
+            };
+            if winning.contains(&(row * game.width() + col)) {
+                symbol = symbol.bold().underlined();
+            }
+            if !game.game_over() && cursor == (Cursor { row, col }) {
+                symbol = symbol.reverse();
+            }
+            queue!(out, Print(" "), PrintStyledContent(symbol), Print(" "))?;
+        }
+        queue!(out, Print("\r\n"))?;
+    }
+    let status = match game.announcement() {
+        Some(announcement) => format!("{announcement} Press any key to leave."),
+        None => format!(
+            "{} to move. Arrows move, Enter places, q quits.",
+            game.player_label(game.current_player())
+        ),
+    };
+    queue!(out, Print("\r\n"), Print(status), Print("\r\n"))?;
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_moves_within_bounds() {
+        let cursor = Cursor::default();
+        assert_eq!(cursor.moved(Direction::Up, 3, 3), cursor);
+        assert_eq!(cursor.moved(Direction::Left, 3, 3), cursor);
+        let cursor = cursor
+            .moved(Direction::Down, 3, 3)
+            .moved(Direction::Right, 3, 3);
+        assert_eq!(cursor, Cursor { row: 1, col: 1 });
+        assert_eq!(cursor.index(3), 4);
+
+        let corner = Cursor { row: 2, col: 2 };
+        assert_eq!(corner.moved(Direction::Down, 3, 3), corner);
+        assert_eq!(corner.moved(Direction::Right, 3, 3), corner);
+        assert_eq!(
+            Cursor { row: 0, col: 3 }.moved(Direction::Right, 5, 3),
+            Cursor { row: 0, col: 4 }
+        );
+    }
+
+    #[test]
+    fn test_key_directions() {
+        assert_eq!(direction(KeyCode::Up), Some(Direction::Up));
+        assert_eq!(direction(KeyCode::Char('l')), Some(Direction::Right));
+        assert_eq!(direction(KeyCode::Enter), None);
+    }
+}
@@ -0,0 +1,51 @@
+use crate::{Game, GameState, Tile, WinningLine};
+
+// Read-only access to a game for renderers and loggers, which can look at it but
+// never play on it
+#[derive(Clone, Copy, Debug)]
+pub struct GameView<'a> {
+    game: &'a Game,
+}
+
+impl<'a> GameView<'a> {
+    pub const fn new(game: &'a Game) -> Self {
+        Self { game }
+    }
+
+    pub fn grid(&self) -> Vec<Vec<Tile>> {
+        self.game.grid()
+    }
+
+    pub const fn state(&self) -> GameState {
+        self.game.state()
+    }
+
+    pub const fn current_player(&self) -> Tile {
+        self.game.current_player()
+    }
+
+    pub fn winning_line(&self) -> Option<WinningLine> {
+        self.game.winning_line()
+    }
+}
+
+impl Game {
+    pub const fn view(&self) -> GameView<'_> {
+        GameView::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_view_matches_game() {
+        let game = Game::from_moves(&[0, 3, 1, 4, 2]).unwrap();
+        let view = game.view();
+        assert_eq!(view.grid(), game.grid());
+        assert_eq!(view.state(), GameState::Win(Tile::X));
+        assert_eq!(view.current_player(), game.current_player());
+        assert_eq!(view.winning_line(), Some(WinningLine::Row(0)));
+    }
+}
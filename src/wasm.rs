@@ -0,0 +1,78 @@
+use crate::{Game, GameState, Tile};
+use wasm_bindgen::prelude::*;
+
+// A Game for JavaScript, using only plain values across the boundary
+#[wasm_bindgen]
+pub struct WasmGame {
+    game: Game,
+}
+
+#[wasm_bindgen]
+impl WasmGame {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self { game: Game::new() }
+    }
+
+    // False if the move was rejected
+    pub fn play(&mut self, index: usize) -> bool {
+        self.game.play(index).is_ok()
+    }
+
+    // One of "in_progress", "x", "o", "y" or "draw". Misere and Wild only take two
+    // players, so the win never passes from Y to Empty
+    pub fn state(&self) -> String {
+        match self.game.state() {
+            GameState::InProgress | GameState::Win(Tile::Empty) => "in_progress",
+            GameState::Win(Tile::X) => "x",
+            GameState::Win(Tile::O) => "o",
+            GameState::Win(Tile::Y) => "y",
+            GameState::Draw => "draw",
+        }
+        .to_string()
+    }
+
+    // Row-major cells, 0 for empty, 1 for X, 2 for O and 3 for Y
+    pub fn grid(&self) -> Vec<u8> {
+        self.game
+            .board_key()
+            .into_iter()
+            .map(|tile| match tile {
+                Tile::Empty => 0,
+                Tile::X => 1,
+                Tile::O => 2,
+                Tile::Y => 3,
+            })
+            .collect()
+    }
+
+    pub fn width(&self) -> usize {
+        self.game.width()
+    }
+}
+
+impl Default for WasmGame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrapper_round_trip() {
+        let mut game = WasmGame::new();
+        assert_eq!(game.state(), "in_progress");
+        assert!(game.play(4));
+        assert!(!game.play(4));
+        assert!(!game.play(9));
+        assert_eq!(game.grid(), vec![0, 0, 0, 0, 1, 0, 0, 0, 0]);
+        for index in [0, 3, 1, 5] {
+            assert!(game.play(index));
+        }
+        assert_eq!(game.grid(), vec![2, 2, 0, 1, 1, 1, 0, 0, 0]);
+        assert_eq!(game.state(), "x");
+    }
+}
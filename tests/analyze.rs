@@ -0,0 +1,29 @@
+use std::process::Command;
+
+fn analyze(notation: &str) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_quick-start"))
+        .arg("analyze")
+        .args(notation.split(' '))
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_analyze_prints_best_move_result_and_threats() {
+    let output = analyze("XX.OO.O.X X");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Best move for X: 2"));
+    assert!(stdout.contains("With perfect play: X wins"));
+    assert!(stdout.contains("X threats: 2"));
+    assert!(stdout.contains("O threats: 2, 5"));
+}
+
+#[test]
+fn test_analyze_rejects_bad_notation() {
+    let output = analyze("XXX");
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .starts_with("Can't analyze that position"));
+}